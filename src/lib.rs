@@ -16,17 +16,6 @@ pub mod common {
     }
 }
 
-/// Private module for constants used in the lib.
-mod consts {
-    #![allow(non_upper_case_globals)]
-    /// Number of bytes in a `usize` (32 bit version).
-    #[cfg(target_pointer_width = "32")]
-    pub const usize_bytes: usize = 4;
-    /// Number of bytes in a `usize` (64 bit version).
-    #[cfg(target_pointer_width = "64")]
-    pub const usize_bytes: usize = 8;
-}
-
 pub mod coll;
 pub mod safe;
 
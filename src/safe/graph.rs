@@ -0,0 +1,457 @@
+//! Directed graphs over wrapped indices.
+//!
+//! Typically used for dependency analysis between definitions indexed by some
+//! [`wrap_usize`](../../macro.wrap_usize.html) index: strongly connected components, topological
+//! order, reachability...
+
+use std::collections::VecDeque;
+
+use crate::safe::int::{IndexVec, IntBitSet, IntRange, IntWrap};
+
+/// Index of the nodes Tarjan's algorithm did not visit yet.
+const UNVISITED: usize = usize::MAX;
+
+/// Directed graph whose nodes are wrapped indices.
+///
+/// This is what the `graph` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as a
+/// type alias. The successors of each node are stored in an
+/// [`IndexVec`](../int/struct.IndexVec.html), sorted and without duplicates, so that all the
+/// traversals and algorithms below are deterministic. Nodes go from `0` to
+/// [`node_count`](#method.node_count) (excluded), adding an edge adds its nodes if needed.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a definition."]
+///     DefIndex
+///     #[doc = "Set of definition indices."]
+///     set: DefSet
+///     #[doc = "Vector indexed by definition indices."]
+///     map: DefMap with iter: DefMapIter
+///     #[doc = "Dependency graph between definitions."]
+///     graph: DefGraph
+/// }
+/// fn main() {
+///     let def = DefIndex::new ;
+///     // Definitions `0` and `1` are mutually recursive, and depend on `2`.
+///     let mut deps: DefMap<DefSet> = DefMap::of_elems( DefSet::new(), 4 ) ;
+///     deps[ def(0) ].insert( def(1) ) ;
+///     deps[ def(1) ].insert( def(0) ) ;
+///     deps[ def(1) ].insert( def(2) ) ;
+///     deps[ def(3) ].insert( def(2) ) ;
+///     let mut graph = DefGraph::of_succs(& deps) ;
+///     assert_eq!( graph.edge_count(), 4 ) ;
+///
+///     // Dependencies come first.
+///     assert_eq!(
+///         graph.sccs(), vec![ vec![ def(2) ], vec![ def(1), def(0) ], vec![ def(3) ] ]
+///     ) ;
+///     assert_eq!( graph.topo_order(), Err( vec![ def(0), def(1) ] ) ) ;
+///     assert!( graph.is_reachable( def(0), def(2) ) ) ;
+///     assert!( ! graph.is_reachable( def(2), def(3) ) ) ;
+///
+///     graph.remove_edge( def(1), def(0) ) ;
+///     graph.add_edge( def(0), def(2) ) ;
+///     assert_eq!( graph.topo_order(), Ok( vec![ def(3), def(0), def(1), def(2) ] ) ) ;
+///     assert_eq!( graph.dfs( def(0) ).collect::<Vec<_>>(), vec![ def(0), def(1), def(2) ] ) ;
+///     // `0 -> 2` is implied by `0 -> 1 -> 2`.
+///     let reduced = graph.transitive_reduction().unwrap() ;
+///     assert!( ! reduced.has_edge( def(0), def(2) ) ) ;
+///     assert_eq!( reduced.edge_count(), 3 ) ;
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph<N> {
+    /// Successors of each node, sorted and without duplicates.
+    succs: IndexVec<N, Vec<N>>,
+    /// Number of edges.
+    edge_count: usize,
+}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> Graph<N> {
+    /// Empty graph.
+    #[inline]
+    pub fn new() -> Self {
+        Graph {
+            succs: IndexVec::new(),
+            edge_count: 0,
+        }
+    }
+    /// Number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.succs.len()
+    }
+    /// Number of edges.
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+    /// True if the graph has no nodes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.succs.is_empty()
+    }
+    /// Successors of all the nodes.
+    #[inline]
+    pub fn succs_map(&self) -> &IndexVec<N, Vec<N>> {
+        &self.succs
+    }
+}
+
+impl<N: IntWrap + From<usize> + Copy> Graph<N> {
+    /// Graph with some nodes and no edges.
+    #[inline]
+    pub fn with_nodes(count: usize) -> Self {
+        Graph {
+            succs: IndexVec::of_elems(Vec::new(), count),
+            edge_count: 0,
+        }
+    }
+    /// Graph with an edge from each node to each of its successors in a map.
+    ///
+    /// The graph has at least as many nodes as the map has entries.
+    pub fn of_succs<C>(succs: &IndexVec<N, C>) -> Self
+    where
+        for<'a> &'a C: IntoIterator<Item = &'a N>,
+    {
+        let mut graph = Self::with_nodes(succs.len());
+        for (node, node_succs) in succs.index_iter() {
+            for succ in node_succs {
+                graph.add_edge(node, *succ);
+            }
+        }
+        graph
+    }
+
+    /// Makes sure some node is in the graph.
+    fn reserve_node(&mut self, node: N) {
+        if self.succs.len() <= node.inner() {
+            self.succs.resize(node.inner() + 1, Vec::new())
+        }
+    }
+    /// Position of a successor of a node.
+    fn search(&self, from: N, to: N) -> Result<usize, usize> {
+        self.succs(from)
+            .binary_search_by_key(&to.inner(), |succ| succ.inner())
+    }
+
+    /// Adds a node without edges.
+    #[inline]
+    pub fn add_node(&mut self) -> N {
+        let node = self.succs.next_index();
+        self.succs.push(Vec::new());
+        node
+    }
+    /// Adds an edge, returns `false` if it was already there.
+    pub fn add_edge(&mut self, from: N, to: N) -> bool {
+        self.reserve_node(from);
+        self.reserve_node(to);
+        match self.search(from, to) {
+            Ok(_) => false,
+            Err(pos) => {
+                self.succs[from].insert(pos, to);
+                self.edge_count += 1;
+                true
+            }
+        }
+    }
+    /// Removes an edge, returns `false` if it was not there.
+    pub fn remove_edge(&mut self, from: N, to: N) -> bool {
+        match self.search(from, to) {
+            Ok(pos) => {
+                self.succs[from].remove(pos);
+                self.edge_count -= 1;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+    /// True if there is an edge from a node to another.
+    #[inline]
+    pub fn has_edge(&self, from: N, to: N) -> bool {
+        self.search(from, to).is_ok()
+    }
+    /// Successors of a node, sorted.
+    #[inline]
+    pub fn succs(&self, node: N) -> &[N] {
+        self.succs.get(node).map(Vec::as_slice).unwrap_or(&[])
+    }
+    /// Iterates over the nodes.
+    #[inline]
+    pub fn nodes(&self) -> IntRange<N> {
+        self.succs.indices()
+    }
+    /// Iterates over the edges, sorted.
+    pub fn edges(&self) -> impl Iterator<Item = (N, N)> + '_ {
+        self.succs
+            .index_iter()
+            .flat_map(|(node, succs)| succs.iter().map(move |succ| (node, *succ)))
+    }
+    /// The graph with all its edges reversed.
+    pub fn reverse(&self) -> Self {
+        let mut reversed = Self::with_nodes(self.node_count());
+        for (from, to) in self.edges() {
+            // Edges come sorted by source, so pushing keeps the successors sorted.
+            reversed.succs[to].push(from)
+        }
+        reversed.edge_count = self.edge_count;
+        reversed
+    }
+
+    /// Depth-first traversal from a node, in pre-order.
+    #[inline]
+    pub fn dfs(&self, start: N) -> Dfs<'_, N> {
+        Dfs {
+            graph: self,
+            stack: vec![start],
+            visited: IntBitSet::new(),
+        }
+    }
+    /// Breadth-first traversal from a node.
+    #[inline]
+    pub fn bfs(&self, start: N) -> Bfs<'_, N> {
+        let mut visited = IntBitSet::new();
+        visited.insert(start);
+        Bfs {
+            graph: self,
+            queue: vec![start].into(),
+            visited,
+        }
+    }
+    /// Nodes reachable from a node, including itself.
+    #[inline]
+    pub fn reachable(&self, from: N) -> IntBitSet<N> {
+        self.dfs(from).collect()
+    }
+    /// True if there is a path from a node to another, always true if they are the same.
+    #[inline]
+    pub fn is_reachable(&self, from: N, to: N) -> bool {
+        self.dfs(from).any(|node| node.inner() == to.inner())
+    }
+
+    /// Strongly connected components, using Tarjan's algorithm.
+    ///
+    /// Components come in reverse topological order: a component comes after all the components
+    /// it has edges to. So, with edges going from definitions to their dependencies,
+    /// dependencies come first. Iterative, does not overflow the stack on deep graphs.
+    pub fn sccs(&self) -> Vec<Vec<N>> {
+        let mut index: IndexVec<N, usize> = IndexVec::of_elems(UNVISITED, self.node_count());
+        let mut low = index.clone();
+        let mut on_stack = IntBitSet::new();
+        let mut stack = Vec::new();
+        let mut call: Vec<(N, ::std::slice::Iter<'_, N>)> = Vec::new();
+        let mut next_index = 0;
+        let mut sccs = Vec::new();
+
+        for root in self.nodes() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack.insert(root);
+            call.push((root, self.succs[root].iter()));
+
+            while let Some((node, succs)) = call.last_mut() {
+                let node = *node;
+                match succs.next() {
+                    Some(&succ) if index[succ] == UNVISITED => {
+                        index[succ] = next_index;
+                        low[succ] = next_index;
+                        next_index += 1;
+                        stack.push(succ);
+                        on_stack.insert(succ);
+                        call.push((succ, self.succs[succ].iter()))
+                    }
+                    Some(succ) => {
+                        if on_stack.contains(succ) {
+                            low[node] = ::std::cmp::min(low[node], index[*succ])
+                        }
+                    }
+                    None => {
+                        call.pop();
+                        if let Some((parent, _)) = call.last() {
+                            low[*parent] = ::std::cmp::min(low[*parent], low[node])
+                        }
+                        if low[node] == index[node] {
+                            let mut scc = Vec::new();
+                            loop {
+                                let member = stack
+                                    .pop()
+                                    .expect("[bug] empty stack in Tarjan's algorithm");
+                                on_stack.remove(&member);
+                                scc.push(member);
+                                if member.inner() == node.inner() {
+                                    break;
+                                }
+                            }
+                            sccs.push(scc)
+                        }
+                    }
+                }
+            }
+        }
+
+        sccs
+    }
+
+    /// Topological order, or a cycle if there is one.
+    ///
+    /// In the order, each node comes before all its successors. Reverse it to have definitions
+    /// come after their dependencies. The cycle is a list of nodes such that each has an edge to
+    /// the next one, and the last one has an edge to the first one.
+    pub fn topo_order(&self) -> Result<Vec<N>, Vec<N>> {
+        let mut done = IntBitSet::new();
+        let mut on_path = IntBitSet::new();
+        let mut path: Vec<(N, ::std::slice::Iter<'_, N>)> = Vec::new();
+        let mut order = Vec::with_capacity(self.node_count());
+
+        for root in self.nodes() {
+            if done.contains(&root) {
+                continue;
+            }
+            on_path.insert(root);
+            path.push((root, self.succs[root].iter()));
+
+            while let Some((node, succs)) = path.last_mut() {
+                let node = *node;
+                match succs.next() {
+                    Some(succ) if on_path.contains(succ) => {
+                        let start = path
+                            .iter()
+                            .position(|(member, _)| member.inner() == succ.inner())
+                            .expect("[bug] node marked as on the path is not on the path");
+                        return Err(path[start..].iter().map(|(member, _)| *member).collect());
+                    }
+                    Some(&succ) => {
+                        if !done.contains(&succ) {
+                            on_path.insert(succ);
+                            path.push((succ, self.succs[succ].iter()))
+                        }
+                    }
+                    None => {
+                        path.pop();
+                        on_path.remove(&node);
+                        done.insert(node);
+                        order.push(node)
+                    }
+                }
+            }
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    /// Transitive reduction, or a cycle if there is one.
+    ///
+    /// The reduction of an acyclic graph is the graph with the fewest edges that has the same
+    /// reachability. It only keeps the edges `from -> to` such that there is no other path from
+    /// `from` to `to`. Quadratic in the number of nodes in the worst case.
+    pub fn transitive_reduction(&self) -> Result<Self, Vec<N>> {
+        let order = self.topo_order()?;
+        // Nodes reachable from each node through at least one edge.
+        let mut reach: IndexVec<N, IntBitSet<N>> =
+            IndexVec::of_elems(IntBitSet::new(), self.node_count());
+        let mut reduced = Self::with_nodes(self.node_count());
+
+        for node in order.into_iter().rev() {
+            let mut indirect = IntBitSet::new();
+            for succ in self.succs(node) {
+                indirect.union_with(&reach[*succ])
+            }
+            let mut node_reach = indirect.clone();
+            for succ in self.succs(node) {
+                if !indirect.contains(succ) {
+                    reduced.add_edge(node, *succ);
+                }
+                node_reach.insert(*succ);
+            }
+            reach[node] = node_reach
+        }
+
+        Ok(reduced)
+    }
+}
+
+impl<N: IntWrap + From<usize> + Copy> Extend<(N, N)> for Graph<N> {
+    fn extend<It: IntoIterator<Item = (N, N)>>(&mut self, edges: It) {
+        for (from, to) in edges {
+            self.add_edge(from, to);
+        }
+    }
+}
+impl<N: IntWrap + From<usize> + Copy> ::std::iter::FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<It: IntoIterator<Item = (N, N)>>(edges: It) -> Self {
+        let mut graph = Self::new();
+        graph.extend(edges);
+        graph
+    }
+}
+
+/// Depth-first traversal of a [`Graph`](struct.Graph.html), in pre-order.
+///
+/// Successors are visited in increasing order.
+#[derive(Clone)]
+pub struct Dfs<'a, N> {
+    /// Graph traversed.
+    graph: &'a Graph<N>,
+    /// Nodes to visit, the next one is on top.
+    stack: Vec<N>,
+    /// Nodes already visited.
+    visited: IntBitSet<N>,
+}
+impl<'a, N: IntWrap + From<usize> + Copy> Iterator for Dfs<'a, N> {
+    type Item = N;
+    fn next(&mut self) -> Option<N> {
+        while let Some(node) = self.stack.pop() {
+            if self.visited.insert(node) {
+                let visited = &self.visited;
+                self.stack.extend(
+                    self.graph
+                        .succs(node)
+                        .iter()
+                        .rev()
+                        .filter(|succ| !visited.contains(succ)),
+                );
+                return Some(node);
+            }
+        }
+        None
+    }
+}
+
+/// Breadth-first traversal of a [`Graph`](struct.Graph.html).
+///
+/// Successors are visited in increasing order.
+#[derive(Clone)]
+pub struct Bfs<'a, N> {
+    /// Graph traversed.
+    graph: &'a Graph<N>,
+    /// Nodes to visit, the next one is in front.
+    queue: VecDeque<N>,
+    /// Nodes visited or in the queue.
+    visited: IntBitSet<N>,
+}
+impl<'a, N: IntWrap + From<usize> + Copy> Iterator for Bfs<'a, N> {
+    type Item = N;
+    fn next(&mut self) -> Option<N> {
+        let node = self.queue.pop_front()?;
+        for succ in self.graph.succs(node) {
+            if self.visited.insert(*succ) {
+                self.queue.push_back(*succ)
+            }
+        }
+        Some(node)
+    }
+}
@@ -0,0 +1,330 @@
+//! Hash consing: sharing structurally equal values behind uniquely identified handles.
+//!
+//! A [`HConsign`](struct.HConsign.html) factory turns values into
+//! [`HConsed`](struct.HConsed.html) handles. Creating a handle for a value equal to the value of
+//! a handle that is still alive yields a clone of that handle. Handles are hence compared and
+//! hashed by their unique identifier, a `u64`, in constant time. Identifiers are drawn from a
+//! global counter, so handles from different factories are never equal.
+//!
+//! Handles implement [`IntWrap64`](../int/trait.IntWrap64.html) and
+//! [`IntHash`](../int/trait.IntHash.html), so they can be stored in
+//! [`HConSet`](type.HConSet.html)s and [`HConMap`](type.HConMap.html)s which use the trivial `u64`
+//! hasher.
+//!
+//! Values are stored once, in their handle. The factory only keeps weak references to the
+//! handles, so a value, and the sub-terms only it refers to, are dropped as soon as its last
+//! handle is. [`collect`](struct.HConsign.html#method.collect) removes the entries of the dead
+//! values from the factory.
+//!
+//! ```
+//! use mylib::safe::hcons::{ HConsign, HConsed, HConSet } ;
+//! // Not `Clone`.
+//! #[derive(Debug, PartialEq, Eq, Hash)]
+//! enum Term {
+//!     Var(usize),
+//!     Add(HConsed<Term>, HConsed<Term>),
+//! }
+//! let mut factory = HConsign::new() ;
+//! let x = factory.mk( Term::Var(0) ) ;
+//! let y = factory.mk( Term::Var(1) ) ;
+//! let x_plus_y = factory.mk( Term::Add(x.clone(), y.clone()) ) ;
+//! assert_eq!( x_plus_y, factory.mk( Term::Add(x.clone(), y.clone()) ) ) ;
+//! assert_ne!( x.uid(), y.uid() ) ;
+//! assert_eq!( factory.len(), 3 ) ;
+//!
+//! let mut set = HConSet::new() ;
+//! set.insert( x.clone() ) ;
+//! set.insert( x_plus_y ) ;
+//! assert!( set.contains(& x) ) ;
+//! assert!( ! set.contains(& y) ) ;
+//!
+//! // `y` is still alive, `x_plus_y` lives in `set`.
+//! drop(y) ;
+//! factory.collect() ;
+//! assert_eq!( factory.len(), 3 ) ;
+//! assert!( factory.get(& Term::Var(1)).is_some() ) ;
+//! // Kills `x_plus_y`, and `y` with it.
+//! drop(set) ;
+//! assert!( factory.get(& Term::Var(1)).is_none() ) ;
+//! factory.collect() ;
+//! assert_eq!( factory.len(), 1 ) ;
+//!
+//! // Handles from different factories are different.
+//! let mut other = HConsign::new() ;
+//! assert_ne!( other.mk( Term::Var(0) ), x ) ;
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Weak};
+
+use crate::common::hash::HashMap;
+use crate::safe::int::{BuildHashU64, IntHMap64, IntHSet64, IntHash, IntWrap64};
+
+/// Hash set of hashconsed things, with a trivial hasher.
+pub type HConSet<T> = IntHSet64<HConsed<T>>;
+/// Hash map from hashconsed things, with a trivial hasher.
+pub type HConMap<T, V> = IntHMap64<HConsed<T>, V>;
+
+/// Actual data behind a handle.
+struct HConsData<T> {
+    /// Unique identifier.
+    uid: u64,
+    /// Value.
+    elm: T,
+}
+
+/// Handle to a hashconsed value.
+///
+/// Cloning a handle is cheap, it only increments a reference counter. Equality, ordering and
+/// hashing only look at the unique identifier of the handle.
+pub struct HConsed<T> {
+    /// Shared data.
+    data: Arc<HConsData<T>>,
+}
+impl<T> HConsed<T> {
+    /// Unique identifier of the handle, unique across all factories.
+    #[inline]
+    pub fn uid(&self) -> u64 {
+        self.data.uid
+    }
+    /// The value.
+    #[inline]
+    pub fn get(&self) -> &T {
+        &self.data.elm
+    }
+    /// Weak version of the handle.
+    #[inline]
+    pub fn to_weak(&self) -> WHConsed<T> {
+        WHConsed {
+            uid: self.data.uid,
+            data: Arc::downgrade(&self.data),
+        }
+    }
+}
+impl<T> Clone for HConsed<T> {
+    fn clone(&self) -> Self {
+        HConsed {
+            data: self.data.clone(),
+        }
+    }
+}
+impl<T> ::std::ops::Deref for HConsed<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.data.elm
+    }
+}
+impl<T> PartialEq for HConsed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.uid() == other.uid()
+    }
+}
+impl<T> Eq for HConsed<T> {}
+impl<T> PartialOrd for HConsed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> Ord for HConsed<T> {
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.uid().cmp(&other.uid())
+    }
+}
+impl<T> Hash for HConsed<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.uid())
+    }
+}
+impl<T> IntHash for HConsed<T> {}
+impl<T> IntWrap64 for HConsed<T> {
+    #[inline]
+    fn inner(&self) -> u64 {
+        self.uid()
+    }
+}
+impl<T: ::std::fmt::Debug> ::std::fmt::Debug for HConsed<T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.data.elm.fmt(fmt)
+    }
+}
+impl<T: ::std::fmt::Display> ::std::fmt::Display for HConsed<T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.data.elm.fmt(fmt)
+    }
+}
+
+/// Weak handle to a hashconsed value, does not keep the value alive.
+pub struct WHConsed<T> {
+    /// Unique identifier, available even if the value is dead.
+    uid: u64,
+    /// Shared data.
+    data: Weak<HConsData<T>>,
+}
+impl<T> WHConsed<T> {
+    /// Unique identifier of the handle.
+    #[inline]
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+    /// Strong version of the handle, `None` if the value is dead.
+    #[inline]
+    pub fn upgrade(&self) -> Option<HConsed<T>> {
+        self.data.upgrade().map(|data| HConsed { data })
+    }
+    /// True if the value is still alive.
+    #[inline]
+    pub fn is_alive(&self) -> bool {
+        self.data.strong_count() > 0
+    }
+}
+impl<T> Clone for WHConsed<T> {
+    fn clone(&self) -> Self {
+        WHConsed {
+            uid: self.uid,
+            data: self.data.clone(),
+        }
+    }
+}
+impl<T> PartialEq for WHConsed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.uid == other.uid
+    }
+}
+impl<T> Eq for WHConsed<T> {}
+impl<T> Hash for WHConsed<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.uid)
+    }
+}
+impl<T> IntHash for WHConsed<T> {}
+impl<T> IntWrap64 for WHConsed<T> {
+    #[inline]
+    fn inner(&self) -> u64 {
+        self.uid
+    }
+}
+impl<T> ::std::fmt::Debug for WHConsed<T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(fmt, "WHConsed({})", self.uid)
+    }
+}
+
+/// Source of the unique identifiers.
+///
+/// Shared by all factories so that handles from different factories never have the same
+/// identifier.
+static NEXT_UID: AtomicU64 = AtomicU64::new(0);
+
+/// Hash consing factory.
+///
+/// Values are only stored in their handle. The table maps the hash of a value to the weak handles
+/// of the values having this hash, and lookups compare the value with the values of the handles
+/// of its bucket that are still alive. Values do not need to be `Clone`.
+pub struct HConsign<T: Hash + Eq> {
+    /// Hashes the values.
+    hasher: RandomState,
+    /// Weak handles of the values created, by hash of their value.
+    table: HashMap<u64, Vec<WHConsed<T>>, BuildHashU64>,
+    /// Number of weak handles in the table.
+    len: usize,
+}
+impl<T: Hash + Eq> Default for HConsign<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Hash + Eq> HConsign<T> {
+    /// Empty factory.
+    #[inline]
+    pub fn new() -> Self {
+        HConsign {
+            hasher: RandomState::new(),
+            table: HashMap::default(),
+            len: 0,
+        }
+    }
+    /// Empty factory with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        HConsign {
+            hasher: RandomState::new(),
+            table: HashMap::with_capacity_and_hasher(capa, BuildHashU64::default()),
+            len: 0,
+        }
+    }
+    /// Number of entries in the factory, including the dead ones not collected yet.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// True if the factory has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Live handle for a value with some hash, `None` if there is none.
+    fn find(&self, hash: u64, elm: &T) -> Option<HConsed<T>> {
+        self.table
+            .get(&hash)?
+            .iter()
+            .filter_map(WHConsed::upgrade)
+            .find(|hconsed| hconsed.get() == elm)
+    }
+
+    /// Handle for a value.
+    ///
+    /// Returns the existing handle if an equal value is alive, creates a fresh one otherwise.
+    pub fn mk(&mut self, elm: T) -> HConsed<T> {
+        let hash = self.hasher.hash_one(&elm);
+        if let Some(hconsed) = self.find(hash, &elm) {
+            return hconsed;
+        }
+        let hconsed = HConsed {
+            data: Arc::new(HConsData {
+                uid: NEXT_UID.fetch_add(1, AtomicOrdering::Relaxed),
+                elm,
+            }),
+        };
+        let bucket = self.table.entry(hash).or_default();
+        if let Some(dead) = bucket.iter_mut().find(|weak| !weak.is_alive()) {
+            *dead = hconsed.to_weak()
+        } else {
+            bucket.push(hconsed.to_weak());
+            self.len += 1
+        }
+        hconsed
+    }
+    /// Handle for a value, `None` if no equal value is alive.
+    pub fn get(&self, elm: &T) -> Option<HConsed<T>> {
+        self.find(self.hasher.hash_one(elm), elm)
+    }
+
+    /// Removes the entries of the values that are dead.
+    pub fn collect(&mut self) {
+        let mut len = 0;
+        self.table.retain(|_, bucket| {
+            bucket.retain(WHConsed::is_alive);
+            len += bucket.len();
+            !bucket.is_empty()
+        });
+        self.len = len
+    }
+    /// Removes the entries of the values that are dead, and shrinks the table.
+    pub fn collect_and_shrink(&mut self) {
+        self.collect();
+        for bucket in self.table.values_mut() {
+            bucket.shrink_to_fit()
+        }
+        self.table.shrink_to_fit()
+    }
+}
+impl<T: Hash + Eq> ::std::fmt::Debug for HConsign<T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_struct("HConsign")
+            .field("len", &self.len)
+            .finish()
+    }
+}
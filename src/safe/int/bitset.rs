@@ -0,0 +1,343 @@
+//! Dense bit-vector set of wrapped indices.
+
+use std::marker::PhantomData;
+
+use super::IntWrap;
+
+/// Type of the words storing the bits.
+type Word = u64;
+/// Number of bits in a `Word`.
+const WORD_BITS: usize = 64;
+
+/// Word index and bit mask of an index.
+#[inline]
+fn word_and_mask(index: usize) -> (usize, Word) {
+    (index / WORD_BITS, 1 << (index % WORD_BITS))
+}
+
+/// Dense set of wrapped indices, one bit per index.
+///
+/// Memory is proportional to the greatest index in the set, not to the number of elements. Better
+/// than [`IntHSet`](struct.IntHSet.html) for sets that are nearly dense.
+///
+/// Iteration is ordered, and yields the wrapper type.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Dense set of variable indices."]
+///     bitset: VarBitSet
+/// }
+/// fn main() {
+///     let mut set = VarBitSet::new() ;
+///     assert!( set.insert( 70.into() ) ) ;
+///     assert!( set.insert( 3.into() ) ) ;
+///     assert!( ! set.insert( 70.into() ) ) ;
+///     assert_eq!( set.len(), 2 ) ;
+///     let elems: Vec<VarIndex> = set.iter().collect() ;
+///     assert_eq!( elems, vec![ VarIndex::new(3), VarIndex::new(70) ] ) ;
+///
+///     let other: VarBitSet = vec![ VarIndex::new(3), VarIndex::new(7) ].into_iter().collect() ;
+///     assert_eq!( ( & set & & other ).len(), 1 ) ;
+///     assert_eq!( ( & set | & other ).len(), 3 ) ;
+///     assert!( ( & set - & other ).contains( & 70.into() ) ) ;
+///     assert!( ! set.is_subset(& other) ) ;
+/// }
+/// ```
+pub struct IntBitSet<Int> {
+    /// Bits.
+    words: Vec<Word>,
+    /// Phantom data for the index type.
+    _int: PhantomData<Int>,
+}
+
+impl<Int> Clone for IntBitSet<Int> {
+    fn clone(&self) -> Self {
+        IntBitSet {
+            words: self.words.clone(),
+            _int: PhantomData,
+        }
+    }
+}
+impl<Int> Default for IntBitSet<Int> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Int> IntBitSet<Int> {
+    /// Empty set.
+    #[inline]
+    pub fn new() -> Self {
+        IntBitSet {
+            words: Vec::new(),
+            _int: PhantomData,
+        }
+    }
+    /// Empty set able to store indices up to `capa` without reallocating.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        IntBitSet {
+            words: Vec::with_capacity(capa.div_ceil(WORD_BITS)),
+            _int: PhantomData,
+        }
+    }
+    /// Number of elements in the set (popcount).
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+    /// True if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+    /// Number of indices the set can store without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.words.capacity() * WORD_BITS
+    }
+    /// Clears the set, keeping the memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.clear()
+    }
+    /// Releases the memory not needed by the current elements.
+    pub fn shrink_to_fit(&mut self) {
+        self.trim();
+        self.words.shrink_to_fit()
+    }
+
+    /// Removes the trailing empty words.
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    /// Adds all the elements of `other` to `self`.
+    pub fn union_with(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0)
+        }
+        for (mine, theirs) in self.words.iter_mut().zip(&other.words) {
+            *mine |= *theirs
+        }
+    }
+    /// Removes the elements of `self` that are not in `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.words.truncate(other.words.len());
+        for (mine, theirs) in self.words.iter_mut().zip(&other.words) {
+            *mine &= *theirs
+        }
+    }
+    /// Removes the elements of `other` from `self`.
+    pub fn difference_with(&mut self, other: &Self) {
+        for (mine, theirs) in self.words.iter_mut().zip(&other.words) {
+            *mine &= !*theirs
+        }
+    }
+    /// Keeps the elements that are in exactly one of `self` and `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0)
+        }
+        for (mine, theirs) in self.words.iter_mut().zip(&other.words) {
+            *mine ^= *theirs
+        }
+    }
+
+    /// True if all the elements of `self` are in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words.iter().enumerate().all(|(idx, mine)| {
+            let theirs = other.words.get(idx).cloned().unwrap_or(0);
+            mine & !theirs == 0
+        })
+    }
+    /// True if all the elements of `other` are in `self`.
+    #[inline]
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+    /// True if `self` and `other` have no element in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(mine, theirs)| mine & theirs == 0)
+    }
+}
+
+impl<Int: IntWrap> IntBitSet<Int> {
+    /// Adds an element, returns `true` if it was not already there.
+    pub fn insert(&mut self, elem: Int) -> bool {
+        let (word, mask) = word_and_mask(elem.inner());
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0)
+        }
+        let is_new = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        is_new
+    }
+    /// Removes an element, returns `true` if it was there.
+    pub fn remove(&mut self, elem: &Int) -> bool {
+        let (word, mask) = word_and_mask(elem.inner());
+        if let Some(word) = self.words.get_mut(word) {
+            let was_there = *word & mask != 0;
+            *word &= !mask;
+            was_there
+        } else {
+            false
+        }
+    }
+    /// True if the set contains an element.
+    pub fn contains(&self, elem: &Int) -> bool {
+        let (word, mask) = word_and_mask(elem.inner());
+        self.words
+            .get(word)
+            .map(|word| word & mask != 0)
+            .unwrap_or(false)
+    }
+}
+
+impl<Int: From<usize>> IntBitSet<Int> {
+    /// Iterates over the elements in increasing order.
+    #[inline]
+    pub fn iter(&self) -> IntBitSetIter<'_, Int> {
+        IntBitSetIter {
+            words: &self.words,
+            word_index: 0,
+            current: self.words.first().cloned().unwrap_or(0),
+            _int: PhantomData,
+        }
+    }
+}
+
+impl<Int> PartialEq for IntBitSet<Int> {
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        short[..] == long[..short.len()] && long[short.len()..].iter().all(|w| *w == 0)
+    }
+}
+impl<Int> Eq for IntBitSet<Int> {}
+impl<Int> ::std::hash::Hash for IntBitSet<Int> {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        let mut len = self.words.len();
+        while len > 0 && self.words[len - 1] == 0 {
+            len -= 1
+        }
+        self.words[..len].hash(state)
+    }
+}
+
+impl<Int: From<usize> + ::std::fmt::Debug> ::std::fmt::Debug for IntBitSet<Int> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<Int: IntWrap> ::std::iter::FromIterator<Int> for IntBitSet<Int> {
+    fn from_iter<I: IntoIterator<Item = Int>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+impl<Int: IntWrap> ::std::iter::Extend<Int> for IntBitSet<Int> {
+    fn extend<I: IntoIterator<Item = Int>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+impl<'a, Int: 'a + IntWrap + Copy> ::std::iter::Extend<&'a Int> for IntBitSet<Int> {
+    fn extend<I: IntoIterator<Item = &'a Int>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(*elem);
+        }
+    }
+}
+impl<'a, Int: From<usize>> IntoIterator for &'a IntBitSet<Int> {
+    type Item = Int;
+    type IntoIter = IntBitSetIter<'a, Int>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, Int> ::std::ops::BitOr<&'a IntBitSet<Int>> for &'a IntBitSet<Int> {
+    type Output = IntBitSet<Int>;
+    /// Union.
+    fn bitor(self, other: &'a IntBitSet<Int>) -> IntBitSet<Int> {
+        let mut res = self.clone();
+        res.union_with(other);
+        res
+    }
+}
+impl<'a, Int> ::std::ops::BitAnd<&'a IntBitSet<Int>> for &'a IntBitSet<Int> {
+    type Output = IntBitSet<Int>;
+    /// Intersection.
+    fn bitand(self, other: &'a IntBitSet<Int>) -> IntBitSet<Int> {
+        let mut res = self.clone();
+        res.intersect_with(other);
+        res
+    }
+}
+impl<'a, Int> ::std::ops::BitXor<&'a IntBitSet<Int>> for &'a IntBitSet<Int> {
+    type Output = IntBitSet<Int>;
+    /// Symmetric difference.
+    fn bitxor(self, other: &'a IntBitSet<Int>) -> IntBitSet<Int> {
+        let mut res = self.clone();
+        res.symmetric_difference_with(other);
+        res
+    }
+}
+impl<'a, Int> ::std::ops::Sub<&'a IntBitSet<Int>> for &'a IntBitSet<Int> {
+    type Output = IntBitSet<Int>;
+    /// Difference.
+    fn sub(self, other: &'a IntBitSet<Int>) -> IntBitSet<Int> {
+        let mut res = self.clone();
+        res.difference_with(other);
+        res
+    }
+}
+
+/// Iterator over the elements of an [`IntBitSet`](struct.IntBitSet.html), in increasing order.
+pub struct IntBitSetIter<'a, Int> {
+    /// Words of the set.
+    words: &'a [Word],
+    /// Index of the current word.
+    word_index: usize,
+    /// Bits of the current word not yielded yet.
+    current: Word,
+    /// Phantom data for the index type.
+    _int: PhantomData<Int>,
+}
+impl<'a, Int> Clone for IntBitSetIter<'a, Int> {
+    fn clone(&self) -> Self {
+        IntBitSetIter {
+            words: self.words,
+            word_index: self.word_index,
+            current: self.current,
+            _int: PhantomData,
+        }
+    }
+}
+impl<'a, Int: From<usize>> Iterator for IntBitSetIter<'a, Int> {
+    type Item = Int;
+    fn next(&mut self) -> Option<Int> {
+        while self.current == 0 {
+            self.word_index += 1;
+            self.current = *self.words.get(self.word_index)?
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // Unset lowest bit.
+        self.current &= self.current - 1;
+        Some((self.word_index * WORD_BITS + bit).into())
+    }
+}
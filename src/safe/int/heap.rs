@@ -0,0 +1,254 @@
+//! Indexed priority queue over wrapped indices.
+
+use super::{IndexVec, IntWrap};
+
+/// Position of the indices that are not in the heap.
+const ABSENT: usize = usize::MAX;
+
+/// Indexed binary max-heap of wrapped indices.
+///
+/// Priorities and positions in the heap are stored in [`IndexVec`](struct.IndexVec.html)s indexed
+/// by the indices, so that [`update`](#method.update), [`remove`](#method.remove) and
+/// [`contains`](#method.contains) do not need to look for the index in the heap. `push`, `pop`,
+/// `update` and `remove` run in `O(log n)`, `contains` in `O(1)`.
+///
+/// Priorities are kept when an index leaves the heap, typically for the activities of variables in
+/// a SAT solver. Use `std::cmp::Reverse` priorities for a min-heap.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::IntHeap ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+/// }
+/// fn main() {
+///     let mut heap: IntHeap<VarIndex, u32> = IntHeap::new() ;
+///     let (v_0, v_1, v_2) = ( VarIndex::new(0), VarIndex::new(1), VarIndex::new(2) ) ;
+///     heap.push(v_0, 3) ;
+///     heap.push(v_1, 7) ;
+///     heap.push(v_2, 5) ;
+///     assert_eq!( heap.peek(), Some( (v_1, & 7) ) ) ;
+///     heap.update(v_0, 10) ;
+///     heap.update_with( v_2, |prio| * prio += 1 ) ;
+///     assert_eq!( heap.pop(), Some(v_0) ) ;
+///     assert!( heap.remove(v_2) ) ;
+///     assert!( ! heap.contains(v_2) ) ;
+///     assert_eq!( heap.priority(v_2), Some(& 6) ) ;
+///     assert_eq!( heap.pop(), Some(v_1) ) ;
+///     assert_eq!( heap.pop(), None ) ;
+///     // `v_2` gets its old priority back.
+///     assert!( heap.reinsert(v_2) ) ;
+///     assert_eq!( heap.peek(), Some( (v_2, & 6) ) ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct IntHeap<I, P> {
+    /// The heap.
+    heap: Vec<I>,
+    /// Position of the indices in the heap, `ABSENT` if not there.
+    pos: IndexVec<I, usize>,
+    /// Priorities of the indices.
+    prio: IndexVec<I, Option<P>>,
+}
+
+impl<I, P> Default for IntHeap<I, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, P> IntHeap<I, P> {
+    /// Empty heap.
+    #[inline]
+    pub fn new() -> Self {
+        IntHeap {
+            heap: Vec::new(),
+            pos: IndexVec::new(),
+            prio: IndexVec::new(),
+        }
+    }
+    /// Empty heap with some capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        IntHeap {
+            heap: Vec::with_capacity(capacity),
+            pos: IndexVec::with_capacity(capacity),
+            prio: IndexVec::with_capacity(capacity),
+        }
+    }
+    /// Number of indices in the heap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+    /// True if the heap is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+    /// Iterates over the indices in the heap, in no particular order.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, I> {
+        self.heap.iter()
+    }
+    /// The priorities, `None` for the indices that never had one.
+    #[inline]
+    pub fn priorities(&self) -> &IndexVec<I, Option<P>> {
+        &self.prio
+    }
+}
+
+impl<I: IntWrap + From<usize> + Copy, P: Ord> IntHeap<I, P> {
+    /// Makes sure the position and priority maps can be accessed with some index.
+    fn reserve_index(&mut self, idx: I) {
+        let len = idx.inner() + 1;
+        if self.pos.len() < len {
+            self.pos.resize(len, ABSENT);
+            self.prio.resize_with(len, || None)
+        }
+    }
+    /// Priority of an index in the heap.
+    #[inline]
+    fn prio_of(&self, idx: I) -> &P {
+        self.prio[idx]
+            .as_ref()
+            .expect("[bug] index in the heap without a priority")
+    }
+    /// Moves the index at some position up until the heap property holds.
+    fn sift_up(&mut self, mut pos: usize) {
+        let idx = self.heap[pos];
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.prio_of(self.heap[parent]) >= self.prio_of(idx) {
+                break;
+            }
+            self.heap[pos] = self.heap[parent];
+            self.pos[self.heap[pos]] = pos;
+            pos = parent
+        }
+        self.heap[pos] = idx;
+        self.pos[idx] = pos
+    }
+    /// Moves the index at some position down until the heap property holds.
+    fn sift_down(&mut self, mut pos: usize) {
+        let idx = self.heap[pos];
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= self.heap.len() {
+                break;
+            }
+            if child + 1 < self.heap.len()
+                && self.prio_of(self.heap[child + 1]) > self.prio_of(self.heap[child])
+            {
+                child += 1
+            }
+            if self.prio_of(idx) >= self.prio_of(self.heap[child]) {
+                break;
+            }
+            self.heap[pos] = self.heap[child];
+            self.pos[self.heap[pos]] = pos;
+            pos = child
+        }
+        self.heap[pos] = idx;
+        self.pos[idx] = pos
+    }
+    /// Restores the heap property for the index at some position, after its priority changed.
+    fn sift(&mut self, pos: usize) {
+        let idx = self.heap[pos];
+        self.sift_up(pos);
+        self.sift_down(self.pos[idx])
+    }
+
+    /// True if an index is in the heap.
+    #[inline]
+    pub fn contains(&self, idx: I) -> bool {
+        self.pos.get(idx).map(|pos| *pos != ABSENT).unwrap_or(false)
+    }
+    /// Priority of an index, `None` if it never had one.
+    #[inline]
+    pub fn priority(&self, idx: I) -> Option<&P> {
+        self.prio.get(idx).and_then(Option::as_ref)
+    }
+    /// Index with the highest priority, and its priority.
+    #[inline]
+    pub fn peek(&self) -> Option<(I, &P)> {
+        self.heap.first().map(|idx| (*idx, self.prio_of(*idx)))
+    }
+
+    /// Inserts an index with some priority, or updates its priority if it is already there.
+    pub fn push(&mut self, idx: I, prio: P) {
+        self.reserve_index(idx);
+        self.prio[idx] = Some(prio);
+        if self.contains(idx) {
+            self.sift(self.pos[idx])
+        } else {
+            self.heap.push(idx);
+            self.sift_up(self.heap.len() - 1)
+        }
+    }
+    /// Inserts an index with the priority it had last, returns `false` if it never had one.
+    ///
+    /// Does nothing if the index is already in the heap.
+    pub fn reinsert(&mut self, idx: I) -> bool {
+        if self.priority(idx).is_none() {
+            false
+        } else {
+            if !self.contains(idx) {
+                self.heap.push(idx);
+                self.sift_up(self.heap.len() - 1)
+            }
+            true
+        }
+    }
+    /// Changes the priority of an index, returns the previous one.
+    ///
+    /// Also works for indices that are not in the heap, which stay out of it.
+    pub fn update(&mut self, idx: I, prio: P) -> Option<P> {
+        self.reserve_index(idx);
+        let old = self.prio[idx].replace(prio);
+        if self.contains(idx) {
+            self.sift(self.pos[idx])
+        }
+        old
+    }
+    /// Changes the priority of an index with a function, `None` if it has no priority.
+    ///
+    /// Also works for indices that are not in the heap, which stay out of it.
+    pub fn update_with<Out, F: FnOnce(&mut P) -> Out>(&mut self, idx: I, f: F) -> Option<Out> {
+        let res = f(self.prio.get_mut(idx)?.as_mut()?);
+        if self.contains(idx) {
+            self.sift(self.pos[idx])
+        }
+        Some(res)
+    }
+    /// Removes the index with the highest priority.
+    pub fn pop(&mut self) -> Option<I> {
+        let idx = *self.heap.first()?;
+        self.remove(idx);
+        Some(idx)
+    }
+    /// Removes an index from the heap, returns `false` if it was not there.
+    ///
+    /// The priority of the index is kept.
+    pub fn remove(&mut self, idx: I) -> bool {
+        if !self.contains(idx) {
+            return false;
+        }
+        let pos = self.pos[idx];
+        self.pos[idx] = ABSENT;
+        let last = self.heap.pop().expect("[bug] removing from an empty heap");
+        if pos < self.heap.len() {
+            self.heap[pos] = last;
+            self.sift(pos)
+        }
+        true
+    }
+    /// Removes all the indices from the heap, keeps their priority.
+    pub fn clear(&mut self) {
+        for idx in self.heap.drain(0..) {
+            self.pos[idx] = ABSENT
+        }
+    }
+}
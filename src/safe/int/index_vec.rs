@@ -0,0 +1,560 @@
+//! Vector indexed by wrapped indices.
+
+use std::iter::Enumerate;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeTo};
+
+use super::{IndexChunks, IndexChunksMut, IndexSlice, IntRange, IntWrap};
+
+/// Wrapper around a vector forcing to use `I` instead of `usize` to access elements.
+///
+/// This is what the `map` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as a
+/// type alias.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::IndexVec ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// /// Works on any typed vector.
+/// fn last_two<I, T>(map: & IndexVec<I, T>) -> Vec<(I, & T)>
+/// where I: mylib::safe::int::IntWrap + From<usize> {
+///     map.index_iter().rev().take(2).collect()
+/// }
+/// fn main() {
+///     let mut map: VarMap<& str> = VarMap::new() ;
+///     map.push("a") ;
+///     map.push("b") ;
+///     map.push("c") ;
+///     assert_eq!( map[ VarIndex::one() ], "b" ) ;
+///     assert_eq!( map.next_index(), VarIndex::new(3) ) ;
+///     assert_eq!(
+///         last_two(& map), vec![ (VarIndex::new(2), & "c"), (VarIndex::one(), & "b") ]
+///     ) ;
+/// }
+/// ```
+///
+/// The `Vec` API is available with typed positions.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// fn main() {
+///     let mut map: VarMap<usize> = VarMap::new() ;
+///     map.extend( vec![ 0, 10, 20, 30, 40 ] ) ;
+///     assert_eq!( map.get( VarIndex::new(2) ), Some(& 20) ) ;
+///     assert_eq!( map.get( VarIndex::new(7) ), None ) ;
+///     assert_eq!( map.last_index(), Some( VarIndex::new(4) ) ) ;
+///
+///     map.insert( VarIndex::one(), 5 ) ;
+///     assert_eq!( map.remove( VarIndex::zero() ), 0 ) ;
+///     assert_eq!( map.position(|elem| * elem == 20), Some( VarIndex::new(2) ) ) ;
+///
+///     map.retain( |idx, _| idx != VarIndex::one() ) ;
+///     assert_eq!( * map, vec![ 5, 20, 30, 40 ] ) ;
+///
+///     let drained: Vec<_> = map.drain( VarIndex::one() .. VarIndex::new(3) ).collect() ;
+///     assert_eq!( drained, vec![ 20, 30 ] ) ;
+///     map.truncate(1) ;
+///     map.resize_with(3, || 7) ;
+///     assert_eq!( * map, vec![ 5, 7, 7 ] ) ;
+/// }
+/// ```
+///
+/// Pushing can return the index of the new element.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a term."]
+///     TermIndex
+///     #[doc = "Range over `TermIndex`."]
+///     range: TermRange
+///     #[doc = "Vector indexed by term indices."]
+///     map: TermMap with iter: TermMapIter
+/// }
+/// fn main() {
+///     let mut terms: TermMap<String> = TermMap::new() ;
+///     let x = terms.push_idx( "x".into() ) ;
+///     let y = terms.push_with( |idx| format!("y_{}", idx) ) ;
+///     assert_eq!( x, TermIndex::zero() ) ;
+///     assert_eq!( terms[y], "y_1" ) ;
+///
+///     let range: TermRange = terms.extend_idx( vec![ "a".into(), "b".into() ] ) ;
+///     let indices: Vec<_> = range.collect() ;
+///     assert_eq!( indices, vec![ TermIndex::new(2), TermIndex::new(3) ] ) ;
+/// }
+/// ```
+pub struct IndexVec<I, T> {
+    /// Elements.
+    vec: Vec<T>,
+    /// Phantom data for the index type.
+    _index: PhantomData<I>,
+}
+
+impl<I, T> Default for IndexVec<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<I, T: Clone> Clone for IndexVec<I, T> {
+    fn clone(&self) -> Self {
+        IndexVec::of(self.vec.clone())
+    }
+}
+impl<I, T: ::std::fmt::Debug> ::std::fmt::Debug for IndexVec<I, T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_struct("IndexVec")
+            .field("vec", &self.vec)
+            .finish()
+    }
+}
+
+impl<I, T> IndexVec<I, T> {
+    /// Creates a map from an existing vector.
+    #[inline]
+    pub fn of(vec: Vec<T>) -> Self {
+        IndexVec {
+            vec,
+            _index: PhantomData,
+        }
+    }
+    /// Creates an empty map.
+    #[inline]
+    pub fn new() -> Self {
+        IndexVec::of(Vec::new())
+    }
+    /// Creates an empty map with some capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        IndexVec::of(Vec::with_capacity(capacity))
+    }
+    /// Clears a map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    /// Number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    /// True if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    /// Capacity of the map.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+    /// Pushes an element.
+    #[inline]
+    pub fn push(&mut self, elem: T) {
+        self.vec.push(elem)
+    }
+    /// Pops an element.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    /// Iterates over the elements.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, T> {
+        self.vec.iter()
+    }
+    /// Iterates over the elements (mutable version).
+    #[inline]
+    pub fn iter_mut(&mut self) -> ::std::slice::IterMut<'_, T> {
+        self.vec.iter_mut()
+    }
+    /// Shrinks the capacity as much as possible.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit()
+    }
+    /// Shortens the map, keeping the first `len` elements.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.vec.truncate(len)
+    }
+    /// Resizes the map so that its length is `len`, using `f` to create new elements.
+    #[inline]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, len: usize, f: F) {
+        self.vec.resize_with(len, f)
+    }
+    /// Moves all the elements of `other` at the end of `self`, leaving `other` empty.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        self.vec.append(&mut other.vec)
+    }
+    /// Turns the map into the underlying vector.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+    /// The map as a typed slice.
+    #[inline]
+    pub fn as_slice(&self) -> &IndexSlice<I, T> {
+        IndexSlice::from_raw(&self.vec)
+    }
+    /// The map as a typed slice, mutable version.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut IndexSlice<I, T> {
+        IndexSlice::from_raw_mut(&mut self.vec)
+    }
+    /// Iterates over typed chunks of `size` elements, see `IndexSlice::chunks`.
+    #[inline]
+    pub fn chunks(&self, size: usize) -> IndexChunks<'_, I, T> {
+        self.as_slice().chunks(size)
+    }
+    /// Iterates over typed chunks of `size` elements, see `IndexSlice::chunks_mut`.
+    #[inline]
+    pub fn chunks_mut(&mut self, size: usize) -> IndexChunksMut<'_, I, T> {
+        self.as_mut_slice().chunks_mut(size)
+    }
+}
+
+impl<I: IntWrap + From<usize>, T> IndexVec<I, T> {
+    /// The next free index (wrapped `self.len()`).
+    #[inline]
+    pub fn next_index(&self) -> I {
+        self.len().into()
+    }
+    /// Pushes an element and returns its index.
+    #[inline]
+    pub fn push_idx(&mut self, elem: T) -> I {
+        let idx = self.next_index();
+        self.vec.push(elem);
+        idx
+    }
+    /// Pushes the element produced by `f` applied to the index of this element, and returns the
+    /// index.
+    #[inline]
+    pub fn push_with<F: FnOnce(I) -> T>(&mut self, f: F) -> I {
+        let elem = f(self.next_index());
+        self.push_idx(elem)
+    }
+    /// Pushes some elements and returns the range of their indices.
+    #[inline]
+    pub fn extend_idx<It: IntoIterator<Item = T>>(&mut self, iter: It) -> IntRange<I> {
+        let start = self.len();
+        self.vec.extend(iter);
+        IntRange::new(start, self.len())
+    }
+    /// The index of the last element, `None` if the map is empty.
+    #[inline]
+    pub fn last_index(&self) -> Option<I> {
+        self.len().checked_sub(1).map(I::from)
+    }
+    /// Range of all the indices of the map.
+    #[inline]
+    pub fn indices(&self) -> IntRange<I> {
+        IntRange::zero_to(self.len())
+    }
+    /// Reference to an element, `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: I) -> Option<&T> {
+        self.vec.get(idx.inner())
+    }
+    /// Mutable reference to an element, `None` if the index is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, idx: I) -> Option<&mut T> {
+        self.vec.get_mut(idx.inner())
+    }
+    /// Index of the first element verifying a predicate.
+    #[inline]
+    pub fn position<F: FnMut(&T) -> bool>(&self, pred: F) -> Option<I> {
+        self.vec.iter().position(pred).map(I::from)
+    }
+    /// Inserts an element at some index, shifting all the elements after it.
+    ///
+    /// Panics if `idx` is greater than the length of the map.
+    #[inline]
+    pub fn insert(&mut self, idx: I, elem: T) {
+        self.vec.insert(idx.inner(), elem)
+    }
+    /// Removes the element at some index, shifting all the elements after it.
+    ///
+    /// Panics if `idx` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, idx: I) -> T {
+        self.vec.remove(idx.inner())
+    }
+    /// Splits the map in two at some index, `self` keeps the elements before `idx`.
+    ///
+    /// Panics if `idx` is greater than the length of the map.
+    #[inline]
+    pub fn split_off(&mut self, idx: I) -> Self {
+        IndexVec::of(self.vec.split_off(idx.inner()))
+    }
+    /// Retains the elements verifying a predicate.
+    ///
+    /// The predicate is given the index of the element **before** any element is removed.
+    pub fn retain<F: FnMut(I, &T) -> bool>(&mut self, mut pred: F) {
+        let mut idx = 0;
+        self.vec.retain(|elem| {
+            let keep = pred(I::from(idx), elem);
+            idx += 1;
+            keep
+        })
+    }
+    /// Retains the elements verifying a predicate, mutable version.
+    ///
+    /// The predicate is given the index of the element **before** any element is removed.
+    pub fn retain_mut<F: FnMut(I, &mut T) -> bool>(&mut self, mut pred: F) {
+        let mut idx = 0;
+        self.vec.retain_mut(|elem| {
+            let keep = pred(I::from(idx), elem);
+            idx += 1;
+            keep
+        })
+    }
+    /// Removes a range of elements and iterates over them.
+    ///
+    /// Panics if the range is out of bounds.
+    #[inline]
+    pub fn drain<R: RangeBounds<I>>(&mut self, range: R) -> ::std::vec::Drain<'_, T> {
+        let start = range.start_bound().map(IntWrap::inner);
+        let end = range.end_bound().map(IntWrap::inner);
+        self.vec.drain((start, end))
+    }
+    /// Iterates over the elements with the index.
+    #[inline]
+    pub fn index_iter(&self) -> IndexIter<I, ::std::slice::Iter<'_, T>> {
+        IndexIter::new(self.vec.iter())
+    }
+    /// Iterates over the elements with the index, mutable version.
+    #[inline]
+    pub fn index_iter_mut(&mut self) -> IndexIter<I, ::std::slice::IterMut<'_, T>> {
+        IndexIter::new(self.vec.iter_mut())
+    }
+    /// Iterates over the elements with the index.
+    #[inline]
+    pub fn into_index_iter(self) -> IndexIter<I, ::std::vec::IntoIter<T>> {
+        IndexIter::new(self.vec.into_iter())
+    }
+    /// Swap from `Vec`.
+    #[inline]
+    pub fn swap(&mut self, a: I, b: I) {
+        self.vec.swap(a.inner(), b.inner())
+    }
+    /// Swap remove from `Vec`.
+    #[inline]
+    pub fn swap_remove(&mut self, idx: I) -> T {
+        self.vec.swap_remove(idx.inner())
+    }
+    /// Splits the map in two typed slices, see `IndexSlice::split_at`.
+    #[inline]
+    pub fn split_at(&self, mid: I) -> (&IndexSlice<I, T>, &IndexSlice<I, T>) {
+        self.as_slice().split_at(mid)
+    }
+    /// Splits the map in two typed slices, see `IndexSlice::split_at_mut`.
+    #[inline]
+    pub fn split_at_mut(&mut self, mid: I) -> (&mut IndexSlice<I, T>, &mut IndexSlice<I, T>) {
+        self.as_mut_slice().split_at_mut(mid)
+    }
+    /// Binary search in a sorted map, see `IndexSlice::binary_search`.
+    #[inline]
+    pub fn binary_search(&self, elem: &T) -> Result<I, I>
+    where
+        T: Ord,
+    {
+        self.as_slice().binary_search(elem)
+    }
+    /// Binary search in a sorted map, see `IndexSlice::binary_search_by`.
+    #[inline]
+    pub fn binary_search_by<F>(&self, f: F) -> Result<I, I>
+    where
+        F: FnMut(&T) -> ::std::cmp::Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+    /// Binary search in a sorted map, see `IndexSlice::binary_search_by_key`.
+    #[inline]
+    pub fn binary_search_by_key<B: Ord, F>(&self, key: &B, f: F) -> Result<I, I>
+    where
+        F: FnMut(&T) -> B,
+    {
+        self.as_slice().binary_search_by_key(key, f)
+    }
+}
+
+impl<I, T: Clone> IndexVec<I, T> {
+    /// Creates a vector containing `size` copies of `elem`.
+    #[inline]
+    pub fn of_elems(elem: T, size: usize) -> Self {
+        IndexVec::of(vec![elem; size])
+    }
+    /// Resizes the map so that its length is `len`, filling it with copies of `elem`.
+    #[inline]
+    pub fn resize(&mut self, len: usize, elem: T) {
+        self.vec.resize(len, elem)
+    }
+}
+
+impl<I, T: PartialEq> PartialEq for IndexVec<I, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.vec.eq(&other.vec)
+    }
+}
+impl<I, T: Eq> Eq for IndexVec<I, T> {}
+impl<I, T: PartialOrd> PartialOrd for IndexVec<I, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        self.vec.partial_cmp(&other.vec)
+    }
+}
+impl<I, T: Ord> Ord for IndexVec<I, T> {
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.vec.cmp(&other.vec)
+    }
+}
+impl<I, T: ::std::hash::Hash> ::std::hash::Hash for IndexVec<I, T> {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        for elem in &self.vec {
+            elem.hash(state)
+        }
+    }
+}
+
+impl<I, T> From<Vec<T>> for IndexVec<I, T> {
+    fn from(vec: Vec<T>) -> Self {
+        IndexVec::of(vec)
+    }
+}
+impl<I, T> IntoIterator for IndexVec<I, T> {
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+    fn into_iter(self) -> ::std::vec::IntoIter<T> {
+        self.vec.into_iter()
+    }
+}
+impl<'a, I, T> IntoIterator for &'a IndexVec<I, T> {
+    type Item = &'a T;
+    type IntoIter = ::std::slice::Iter<'a, T>;
+    fn into_iter(self) -> ::std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+impl<'a, I, T> IntoIterator for &'a mut IndexVec<I, T> {
+    type Item = &'a mut T;
+    type IntoIter = ::std::slice::IterMut<'a, T>;
+    fn into_iter(self) -> ::std::slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+impl<I, T> ::std::iter::FromIterator<T> for IndexVec<I, T> {
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        IndexVec::of(iter.into_iter().collect())
+    }
+}
+
+impl<I, T> Extend<T> for IndexVec<I, T> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.vec.extend(iter)
+    }
+}
+impl<'a, I, T: 'a + Copy> Extend<&'a T> for IndexVec<I, T> {
+    fn extend<It: IntoIterator<Item = &'a T>>(&mut self, iter: It) {
+        self.vec.extend(iter)
+    }
+}
+
+impl<I: IntWrap, T> Index<I> for IndexVec<I, T> {
+    type Output = T;
+    fn index(&self, index: I) -> &T {
+        &self.vec[index.inner()]
+    }
+}
+impl<I: IntWrap, T> IndexMut<I> for IndexVec<I, T> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.vec[index.inner()]
+    }
+}
+impl<I, T> Index<Range<usize>> for IndexVec<I, T> {
+    type Output = [T];
+    fn index(&self, index: Range<usize>) -> &[T] {
+        self.vec.index(index)
+    }
+}
+impl<I, T> Index<RangeFrom<usize>> for IndexVec<I, T> {
+    type Output = [T];
+    fn index(&self, index: RangeFrom<usize>) -> &[T] {
+        self.vec.index(index)
+    }
+}
+impl<I, T> Index<RangeTo<usize>> for IndexVec<I, T> {
+    type Output = [T];
+    fn index(&self, index: RangeTo<usize>) -> &[T] {
+        self.vec.index(index)
+    }
+}
+impl<I, T> Deref for IndexVec<I, T> {
+    type Target = Vec<T>;
+    fn deref(&self) -> &Vec<T> {
+        &self.vec
+    }
+}
+impl<I, T> DerefMut for IndexVec<I, T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.vec
+    }
+}
+
+/// Iterator over some elements and their index.
+///
+/// `It` is the underlying iterator over the elements.
+#[derive(Clone, Debug)]
+pub struct IndexIter<I, It> {
+    /// Underlying iterator, with `usize` indices.
+    iter: Enumerate<It>,
+    /// Phantom data for the index type.
+    _index: PhantomData<I>,
+}
+impl<I, It: Iterator> IndexIter<I, It> {
+    /// Creates an iterator starting at index `0`.
+    #[inline]
+    pub(crate) fn new(iter: It) -> Self {
+        IndexIter {
+            iter: iter.enumerate(),
+            _index: PhantomData,
+        }
+    }
+}
+impl<I: From<usize>, It: Iterator> Iterator for IndexIter<I, It> {
+    type Item = (I, It::Item);
+    #[inline]
+    fn next(&mut self) -> Option<(I, It::Item)> {
+        self.iter.next().map(|(index, elem)| (index.into(), elem))
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<I, It> DoubleEndedIterator for IndexIter<I, It>
+where
+    I: From<usize>,
+    It: DoubleEndedIterator + ExactSizeIterator,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<(I, It::Item)> {
+        self.iter
+            .next_back()
+            .map(|(index, elem)| (index.into(), elem))
+    }
+}
+impl<I: From<usize>, It: ExactSizeIterator> ExactSizeIterator for IndexIter<I, It> {}
@@ -0,0 +1,222 @@
+//! Bijection between values and wrapped indices.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use super::{BuildHashU64, IndexIter, IndexVec, IntWrap};
+use crate::common::hash::HashMap;
+
+/// Interning table: stores each distinct value once and gives it a wrapped index.
+///
+/// This is what the `interner` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as
+/// a type alias. Values are only stored in an [`IndexVec`](struct.IndexVec.html). Lookups hash the
+/// value, and compare it with the values stored at the indices having the same hash, so that
+/// interning the same value twice yields the same index. Values do not need to be `Clone`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a symbol."]
+///     SymIndex
+///     #[doc = "Symbol table."]
+///     interner: SymTable
+/// }
+/// fn main() {
+///     let mut table: SymTable<String> = SymTable::new() ;
+///     let x = table.intern( "x".into() ) ;
+///     let y = table.intern( "y".into() ) ;
+///     assert_ne!( x, y ) ;
+///     assert_eq!( table.intern( "x".into() ), x ) ;
+///     assert_eq!( table.len(), 2 ) ;
+///     assert_eq!( table.resolve(y), "y" ) ;
+///     assert_eq!( table.get("y"), Some(y) ) ;
+///     assert_eq!( table.get("z"), None ) ;
+/// }
+/// ```
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a term."]
+///     TermIndex
+///     #[doc = "Term table."]
+///     interner: TermTable
+/// }
+/// // Not `Clone`.
+/// #[derive(Hash, PartialEq, Eq, Debug)]
+/// enum Term {
+///     Var(usize),
+///     App(String, Vec<TermIndex>),
+/// }
+/// fn main() {
+///     let mut table: TermTable<Term> = TermTable::new() ;
+///     let x = table.intern( Term::Var(0) ) ;
+///     let f_x = table.intern( Term::App( "f".into(), vec![x] ) ) ;
+///     assert_eq!( table.intern( Term::App( "f".into(), vec![x] ) ), f_x ) ;
+///     assert_eq!( table.get( & Term::Var(0) ), Some(x) ) ;
+///     assert_eq!( table.len(), 2 ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Interner<I, T: Hash + Eq> {
+    /// Values, indexed by their index.
+    values: IndexVec<I, T>,
+    /// Hashes the values.
+    hasher: RandomState,
+    /// Maps the hash of a value to the last index interned with this hash.
+    buckets: HashMap<u64, I, BuildHashU64>,
+    /// Previous index interned with the same hash, for each index.
+    collisions: IndexVec<I, Option<I>>,
+}
+
+impl<I, T: Hash + Eq> Default for Interner<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, T: Hash + Eq> Interner<I, T> {
+    /// Empty table.
+    #[inline]
+    pub fn new() -> Self {
+        Interner {
+            values: IndexVec::new(),
+            hasher: RandomState::new(),
+            buckets: HashMap::default(),
+            collisions: IndexVec::new(),
+        }
+    }
+    /// Empty table with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        Interner {
+            values: IndexVec::with_capacity(capa),
+            hasher: RandomState::new(),
+            buckets: HashMap::with_capacity_and_hasher(capa, BuildHashU64::default()),
+            collisions: IndexVec::with_capacity(capa),
+        }
+    }
+    /// Number of values in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    /// True if the table is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    /// Iterates over the values, in the order they were interned.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+    /// The values, indexed by their index.
+    #[inline]
+    pub fn values(&self) -> &IndexVec<I, T> {
+        &self.values
+    }
+    /// Turns the table into the vector of its values.
+    #[inline]
+    pub fn into_values(self) -> IndexVec<I, T> {
+        self.values
+    }
+}
+
+impl<I: IntWrap + From<usize> + Copy, T: Hash + Eq> Interner<I, T> {
+    /// Index of a value with some hash, `None` if it is not in the table.
+    fn find<Q>(&self, hash: u64, value: &Q) -> Option<I>
+    where
+        T: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let mut current = self.buckets.get(&hash).cloned();
+        while let Some(idx) = current {
+            if self.values[idx].borrow() == value {
+                return Some(idx);
+            }
+            current = self.collisions[idx]
+        }
+        None
+    }
+
+    /// Index of a value, inserts it in the table if it's not there already.
+    pub fn intern(&mut self, value: T) -> I {
+        let hash = self.hasher.hash_one(&value);
+        if let Some(idx) = self.find(hash, &value) {
+            return idx;
+        }
+        let idx = self.values.push_idx(value);
+        let previous = self.buckets.insert(hash, idx);
+        self.collisions.push(previous);
+        idx
+    }
+    /// Index of a value, `None` if it is not in the table.
+    #[inline]
+    pub fn get<Q>(&self, value: &Q) -> Option<I>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(self.hasher.hash_one(value), value)
+    }
+    /// True if a value is in the table.
+    #[inline]
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(value).is_some()
+    }
+    /// Value of an index.
+    ///
+    /// Panics if the index is out of bounds.
+    #[inline]
+    pub fn resolve(&self, idx: I) -> &T {
+        &self.values[idx]
+    }
+    /// Value of an index, `None` if the index is out of bounds.
+    #[inline]
+    pub fn try_resolve(&self, idx: I) -> Option<&T> {
+        self.values.get(idx)
+    }
+    /// Iterates over the values with their index.
+    #[inline]
+    pub fn index_iter(&self) -> IndexIter<I, ::std::slice::Iter<'_, T>> {
+        self.values.index_iter()
+    }
+}
+
+impl<I: IntWrap, T: Hash + Eq> ::std::ops::Index<I> for Interner<I, T> {
+    type Output = T;
+    fn index(&self, idx: I) -> &T {
+        &self.values[idx]
+    }
+}
+impl<'a, I, T: Hash + Eq> IntoIterator for &'a Interner<I, T> {
+    type Item = &'a T;
+    type IntoIter = ::std::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<I: IntWrap + From<usize> + Copy, T: Hash + Eq> ::std::iter::Extend<T> for Interner<I, T> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        for value in iter {
+            self.intern(value);
+        }
+    }
+}
+impl<I: IntWrap + From<usize> + Copy, T: Hash + Eq> ::std::iter::FromIterator<T>
+    for Interner<I, T>
+{
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        let mut table = Self::new();
+        table.extend(iter);
+        table
+    }
+}
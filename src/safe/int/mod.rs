@@ -0,0 +1,1835 @@
+//! Strongly-typed, zero-cost indices wrapping integers.
+//!
+//! Nothing in this module is meant to be used directly. The
+//! [`wrap_usize`](../../macro.wrap_usize.html) does all the work. The generic collections it
+//! generates aliases for can be used to write code generic over index types.
+//!
+//! Typically used when storing values of some type, say `Term`, in an array.
+//! Usually some things will be associated with these terms (like the term's
+//! *free variables*), which one would usually put in another array understood
+//! as using the same indices as the term array.
+//!
+//! But when doing this for more than one type, there is a strong risk of
+//! using a *term index* for something else. This module wraps `usize`s and
+//! gives specialized collections making it impossible to mix indices
+//! statically.
+//!
+//! **NB**: the wrappers use the trivial hash function for speed since this
+//! library was not written for doing web-oriented things.
+
+use crate::common::hash::*;
+use std::hash::{BuildHasher, Hash};
+
+pub use self::hash::{BuildHashFib, BuildHashU64, BuildHashUsize};
+
+mod bitset;
+mod heap;
+mod index_vec;
+mod interner;
+mod ordered;
+mod par;
+mod range;
+mod slab;
+mod slice;
+mod small_set;
+mod sparse_set;
+mod tagged;
+mod trail;
+mod union_find;
+
+pub use self::bitset::{IntBitSet, IntBitSetIter};
+pub use self::heap::IntHeap;
+pub use self::index_vec::{IndexIter, IndexVec};
+pub use self::interner::Interner;
+pub use self::ordered::{
+    IntOrderedHMap, IntOrderedHMapIntoIter, IntOrderedHMapIter, IntOrderedHMapIterMut,
+    IntOrderedHSet,
+};
+pub use self::par::{FromParIter, ParFilter, ParIndexed, ParIter, ParMap, ParSource};
+pub use self::range::IntRange;
+pub use self::slab::{GenIndex, IntSlab, IntSlabIter, IntSlabIterMut, SlabKey};
+pub use self::slice::{IndexChunks, IndexChunksMut, IndexSlice};
+pub use self::small_set::{
+    IntSmallSet, IntSmallSetDrain, IntSmallSetIntoIter, IntSmallSetIter, IntSmallSetSortedIter,
+};
+pub use self::sparse_set::IntSparseSet;
+pub use self::tagged::{Tagged, TaggedVec};
+pub use self::trail::{TrailHMap, TrailHSet, TrailVec};
+pub use self::union_find::{UnionFind, UnionFindClass};
+
+/// Trivial hashers for [`IntHash`](../trait.IntHash.html) types, which hash as exactly one
+/// integer.
+///
+/// These hashers will cause logic errors if asked to hash anything else than what they are
+/// supposed to hash. In `debug`, hashing anything else panics. In `release`, the integers written
+/// are combined and bytes are folded in the hash, which is slow but not wrong.
+///
+/// - [`BuildHashUsize`](struct.BuildHashUsize.html) (default): the hash is the integer itself,
+///   optimal for indices that are mostly contiguous;
+/// - [`BuildHashFib`](struct.BuildHashFib.html): the integer is mixed by a multiplication, better
+///   for strided or clustered indices (only even indices, indices shifted left...) since hash
+///   tables use some bits of the hash more than others;
+/// - [`BuildHashU64`](struct.BuildHashU64.html): only accepts `u64`s, for
+///   [`IntWrap64`](../trait.IntWrap64.html) types such as the unique identifiers of hashconsed
+///   things.
+pub mod hash {
+    use std::hash::{BuildHasher, Hasher};
+
+    /// Empty struct used to build `HashUsize`.
+    #[derive(Clone, Default, Debug)]
+    pub struct BuildHashUsize {}
+    impl BuildHasher for BuildHashUsize {
+        type Hasher = HashUsize;
+        fn build_hasher(&self) -> HashUsize {
+            HashUsize::default()
+        }
+    }
+
+    /// Trivial hasher for integers. **This hasher is only for hashing one integer**.
+    #[derive(Default)]
+    pub struct HashUsize {
+        /// Current hash.
+        hash: u64,
+        /// True if an integer was written, only used in debug.
+        #[cfg(debug_assertions)]
+        written: bool,
+    }
+    impl HashUsize {
+        /// Writes an integer.
+        ///
+        /// In debug, panics if an integer was already written.
+        #[inline(always)]
+        fn write_int(&mut self, int: u64) {
+            #[cfg(debug_assertions)]
+            {
+                if self.written {
+                    panic!(
+                        "[illegal] `HashUsize` used on a key that does not hash as exactly one \
+                         integer (second integer written)"
+                    )
+                }
+                self.written = true
+            }
+            self.hash = self.hash.rotate_left(5) ^ int
+        }
+    }
+    impl Hasher for HashUsize {
+        #[inline]
+        fn finish(&self) -> u64 {
+            self.hash
+        }
+        fn write(&mut self, bytes: &[u8]) {
+            if cfg!(debug_assertions) {
+                panic!(
+                    "[illegal] `HashUsize` used on a key that does not hash as exactly one \
+                     integer ({} raw bytes written)",
+                    bytes.len()
+                )
+            }
+            for byte in bytes {
+                self.hash = self.hash.rotate_left(8) ^ u64::from(*byte)
+            }
+        }
+        #[inline]
+        fn write_u8(&mut self, int: u8) {
+            self.write_int(int.into())
+        }
+        #[inline]
+        fn write_u16(&mut self, int: u16) {
+            self.write_int(int.into())
+        }
+        #[inline]
+        fn write_u32(&mut self, int: u32) {
+            self.write_int(int.into())
+        }
+        #[inline]
+        fn write_u64(&mut self, int: u64) {
+            self.write_int(int)
+        }
+        #[inline]
+        fn write_usize(&mut self, int: usize) {
+            self.write_int(int as u64)
+        }
+    }
+
+    /// Empty struct used to build `HashU64`.
+    #[derive(Clone, Default, Debug)]
+    pub struct BuildHashU64 {}
+    impl BuildHasher for BuildHashU64 {
+        type Hasher = HashU64;
+        fn build_hasher(&self) -> HashU64 {
+            HashU64::default()
+        }
+    }
+
+    /// Trivial hasher for `u64`. **This hasher is only for hashing one `u64`**.
+    ///
+    /// The hash is the `u64` itself, on 32-bit targets too.
+    #[derive(Default)]
+    pub struct HashU64 {
+        /// Hasher for the integer.
+        int: HashUsize,
+    }
+    impl Hasher for HashU64 {
+        #[inline]
+        fn finish(&self) -> u64 {
+            self.int.finish()
+        }
+        /// Called by all the `write_*` functions except `write_u64`. Panics in debug.
+        #[inline]
+        fn write(&mut self, bytes: &[u8]) {
+            self.int.write(bytes)
+        }
+        #[inline]
+        fn write_u64(&mut self, int: u64) {
+            self.int.write_u64(int)
+        }
+    }
+
+    /// Empty struct used to build `HashFib`.
+    #[derive(Clone, Default, Debug)]
+    pub struct BuildHashFib {}
+    impl BuildHasher for BuildHashFib {
+        type Hasher = HashFib;
+        fn build_hasher(&self) -> HashFib {
+            HashFib::default()
+        }
+    }
+
+    /// Multiplicative (Fibonacci) hasher for integers. **This hasher is only for hashing one
+    /// integer**.
+    ///
+    /// Multiplies the integer by `2^64` divided by the golden ratio, and folds the high half of
+    /// the 128-bit product onto the low half so that all the bits of the hash depend on all the
+    /// bits of the integer.
+    #[derive(Default)]
+    pub struct HashFib {
+        /// Hasher for the integer.
+        int: HashUsize,
+    }
+    impl Hasher for HashFib {
+        #[inline]
+        fn finish(&self) -> u64 {
+            const FIB: u128 = 0x9E37_79B9_7F4A_7C15;
+            let full = u128::from(self.int.finish()) * FIB;
+            (full as u64) ^ ((full >> 64) as u64)
+        }
+        #[inline]
+        fn write(&mut self, bytes: &[u8]) {
+            self.int.write(bytes)
+        }
+        #[inline]
+        fn write_u8(&mut self, int: u8) {
+            self.int.write_u8(int)
+        }
+        #[inline]
+        fn write_u16(&mut self, int: u16) {
+            self.int.write_u16(int)
+        }
+        #[inline]
+        fn write_u32(&mut self, int: u32) {
+            self.int.write_u32(int)
+        }
+        #[inline]
+        fn write_u64(&mut self, int: u64) {
+            self.int.write_u64(int)
+        }
+        #[inline]
+        fn write_usize(&mut self, int: usize) {
+            self.int.write_usize(int)
+        }
+    }
+}
+
+/// Types that hash as exactly one integer.
+///
+/// Only these types can be stored in the collections using the trivial hasher, like
+/// [`IntHSet`](struct.IntHSet.html) and [`IntHMap`](struct.IntHMap.html). Implemented by all the
+/// wrappers generated by [`wrap_usize`](../../macro.wrap_usize.html).
+///
+/// Implementing this trait states that `Hash::hash` makes exactly one call to one of the
+/// `write_u8`, `write_u16`, `write_u32`, `write_u64` or `write_usize` methods of the hasher, and
+/// nothing else. In debug, the trivial hasher panics if this is not the case.
+///
+/// ```
+/// use mylib::safe::int::{ IntHash, IntHSet } ;
+/// /// Some unique identifier.
+/// #[derive(Clone, Copy, PartialEq, Eq)]
+/// struct Uid(u32) ;
+/// impl std::hash::Hash for Uid {
+///     fn hash<H: std::hash::Hasher>(& self, state: & mut H) {
+///         state.write_u32(self.0)
+///     }
+/// }
+/// impl IntHash for Uid {}
+///
+/// let mut set: IntHSet<Uid> = IntHSet::new() ;
+/// set.insert( Uid(7) ) ;
+/// assert!( set.contains( & Uid(7) ) ) ;
+/// ```
+pub trait IntHash: Hash + Eq {}
+
+/// Trait implemented by wrappers.
+///
+/// Implementing this trait iff the `usize` returned is a unique identifier
+/// for `self`.
+pub trait IntWrap {
+    /// Gives access to the inner value.
+    fn inner(&self) -> usize;
+}
+
+/// Trait implemented by `u64` wrappers, the counterpart of [`IntWrap`](trait.IntWrap.html).
+///
+/// Only implement this trait if the `u64` returned is a unique identifier for `self`. Typically
+/// used for the unique identifiers of hashconsed things, or external handles, which should not be
+/// truncated on 32-bit targets.
+///
+/// Such types are usually stored in [`IntHSet64`](type.IntHSet64.html) and
+/// [`IntHMap64`](type.IntHMap64.html), which require them to implement
+/// [`IntHash`](trait.IntHash.html) by writing their `u64` with `write_u64`.
+///
+/// ```
+/// use mylib::safe::int::{ IntHash, IntWrap64, IntHSet64, IntHMap64 } ;
+/// /// Unique identifier of a term.
+/// #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// struct TermUid(u64) ;
+/// impl std::hash::Hash for TermUid {
+///     fn hash<H: std::hash::Hasher>(& self, state: & mut H) {
+///         state.write_u64( self.inner() )
+///     }
+/// }
+/// impl IntHash for TermUid {}
+/// impl IntWrap64 for TermUid {
+///     fn inner(& self) -> u64 { self.0 }
+/// }
+///
+/// let mut set: IntHSet64<TermUid> = IntHSet64::new() ;
+/// set.insert( TermUid(1 << 40) ) ;
+/// assert!( set.contains( & TermUid(1 << 40) ) ) ;
+/// let mut map: IntHMap64<TermUid, & str> = IntHMap64::new() ;
+/// map.insert( TermUid(7), "x" ) ;
+/// assert_eq!( map.get( & TermUid(7) ), Some(& "x") ) ;
+/// ```
+pub trait IntWrap64 {
+    /// Gives access to the inner value.
+    fn inner(&self) -> u64;
+}
+
+/// Hash set of [`IntWrap64`](trait.IntWrap64.html) things, with a trivial `u64` hasher.
+pub type IntHSet64<Int> = IntHSet<Int, BuildHashU64>;
+/// Hash map from [`IntWrap64`](trait.IntWrap64.html) things, with a trivial `u64` hasher.
+pub type IntHMap64<Int, V> = IntHMap<Int, V, BuildHashU64>;
+
+/// Iterator over the elements of an [`IntHSet`](struct.IntHSet.html).
+pub type IntHSetIter<'a, Int> = ::std::collections::hash_set::Iter<'a, Int>;
+/// Owning iterator over the elements of an [`IntHSet`](struct.IntHSet.html).
+pub type IntHSetIntoIter<Int> = ::std::collections::hash_set::IntoIter<Int>;
+/// Draining iterator over the elements of an [`IntHSet`](struct.IntHSet.html).
+pub type IntHSetDrain<'a, Int> = ::std::collections::hash_set::Drain<'a, Int>;
+/// Iterator over the bindings of an [`IntHMap`](struct.IntHMap.html).
+pub type IntHMapIter<'a, Int, V> = ::std::collections::hash_map::Iter<'a, Int, V>;
+/// Iterator over the bindings of an [`IntHMap`](struct.IntHMap.html), mutable version.
+pub type IntHMapIterMut<'a, Int, V> = ::std::collections::hash_map::IterMut<'a, Int, V>;
+/// Owning iterator over the bindings of an [`IntHMap`](struct.IntHMap.html).
+pub type IntHMapIntoIter<Int, V> = ::std::collections::hash_map::IntoIter<Int, V>;
+/// Draining iterator over the bindings of an [`IntHMap`](struct.IntHMap.html).
+pub type IntHMapDrain<'a, Int, V> = ::std::collections::hash_map::Drain<'a, Int, V>;
+/// Iterator over the keys of an [`IntHMap`](struct.IntHMap.html).
+pub type IntHMapKeys<'a, Int, V> = ::std::collections::hash_map::Keys<'a, Int, V>;
+/// Iterator over the values of an [`IntHMap`](struct.IntHMap.html).
+pub type IntHMapValues<'a, Int, V> = ::std::collections::hash_map::Values<'a, Int, V>;
+/// Iterator over the values of an [`IntHMap`](struct.IntHMap.html), mutable version.
+pub type IntHMapValuesMut<'a, Int, V> = ::std::collections::hash_map::ValuesMut<'a, Int, V>;
+/// Entry of an [`IntHMap`](struct.IntHMap.html), see [`entry`](struct.IntHMap.html#method.entry).
+pub type IntHMapEntry<'a, Int, V> = ::std::collections::hash_map::Entry<'a, Int, V>;
+
+/// Iterator over the elements of a hash table in increasing order.
+#[derive(Clone, Debug)]
+enum SortedIter<It, T> {
+    /// The iterator of the table, when it already yields the elements in increasing order.
+    InOrder(It),
+    /// The elements, sorted.
+    Sorted(::std::vec::IntoIter<T>),
+}
+impl<It: Iterator<Item = T>, T> Iterator for SortedIter<It, T> {
+    type Item = T;
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        match *self {
+            SortedIter::InOrder(ref mut iter) => iter.next(),
+            SortedIter::Sorted(ref mut iter) => iter.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            SortedIter::InOrder(ref iter) => iter.size_hint(),
+            SortedIter::Sorted(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// Iterator over the elements of an [`IntHSet`](struct.IntHSet.html) in increasing order, see
+/// [`iter_sorted`](struct.IntHSet.html#method.iter_sorted).
+#[derive(Clone, Debug)]
+pub struct IntHSetSortedIter<'a, Int> {
+    /// Underlying iterator.
+    iter: SortedIter<IntHSetIter<'a, Int>, &'a Int>,
+}
+impl<'a, Int> Iterator for IntHSetSortedIter<'a, Int> {
+    type Item = &'a Int;
+    #[inline]
+    fn next(&mut self) -> Option<&'a Int> {
+        self.iter.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<'a, Int> ExactSizeIterator for IntHSetSortedIter<'a, Int> {}
+
+/// Iterator over the bindings of an [`IntHMap`](struct.IntHMap.html) in increasing order of keys,
+/// see [`iter_sorted`](struct.IntHMap.html#method.iter_sorted).
+#[derive(Clone, Debug)]
+pub struct IntHMapSortedIter<'a, Int, V> {
+    /// Underlying iterator.
+    iter: SortedIter<IntHMapIter<'a, Int, V>, (&'a Int, &'a V)>,
+}
+impl<'a, Int, V> Iterator for IntHMapSortedIter<'a, Int, V> {
+    type Item = (&'a Int, &'a V);
+    #[inline]
+    fn next(&mut self) -> Option<(&'a Int, &'a V)> {
+        self.iter.next()
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+impl<'a, Int, V> ExactSizeIterator for IntHMapSortedIter<'a, Int, V> {}
+
+/// Hasher for the elements of a collection, used by [`unordered_hash`](fn.unordered_hash.html).
+///
+/// Same as [`HashFib`](hash/struct.HashFib.html) for elements that hash as one integer, but
+/// accepts any number of writes so that it also works on the bindings of maps: each integer
+/// written is combined with the Fibonacci hash of the integers written before.
+#[derive(Default)]
+struct HashMix {
+    /// Current hash, before the final mixing.
+    hash: u64,
+}
+impl HashMix {
+    /// Writes an integer.
+    #[inline]
+    fn write_int(&mut self, int: u64) {
+        // The Fibonacci hash of `0` is `0`, so the first integer is written as is.
+        self.hash = BuildHashFib::default().hash_one(self.hash) ^ int
+    }
+}
+impl ::std::hash::Hasher for HashMix {
+    #[inline]
+    fn finish(&self) -> u64 {
+        BuildHashFib::default().hash_one(self.hash)
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_int(u64::from_le_bytes(word))
+        }
+    }
+    #[inline]
+    fn write_u8(&mut self, int: u8) {
+        self.write_int(int.into())
+    }
+    #[inline]
+    fn write_u16(&mut self, int: u16) {
+        self.write_int(int.into())
+    }
+    #[inline]
+    fn write_u32(&mut self, int: u32) {
+        self.write_int(int.into())
+    }
+    #[inline]
+    fn write_u64(&mut self, int: u64) {
+        self.write_int(int)
+    }
+    #[inline]
+    fn write_usize(&mut self, int: usize) {
+        self.write_int(int as u64)
+    }
+}
+
+/// Order-independent hash of some elements.
+///
+/// Wrapping sum of the hashes of the elements, each obtained with a fresh
+/// [`HashMix`](struct.HashMix.html), that is a multiplication for elements that hash as one
+/// integer. Written as a single `u64` so that it works with any hasher.
+fn unordered_hash<T: Hash, It: Iterator<Item = T>>(elems: It) -> u64 {
+    elems.fold(0, |sum, elem| {
+        let mut hasher = HashMix::default();
+        elem.hash(&mut hasher);
+        sum.wrapping_add(::std::hash::Hasher::finish(&hasher))
+    })
+}
+
+/// Wraps a hash set with a trivial hasher.
+///
+/// The hasher `S` defaults to the trivial one, see the [`hash`](hash/index.html) module for the
+/// alternatives.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::{ IntHSet, BuildHashFib } ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+/// }
+/// fn main() {
+///     let mut set = VarSet::new() ;
+///     set.insert( VarIndex::new(7) ) ;
+///     // Better for strided indices.
+///     let mut fib: IntHSet<VarIndex, BuildHashFib> = IntHSet::new() ;
+///     fib.extend( (0..100).map(|n| VarIndex::new(n << 8)) ) ;
+///     assert!( fib.contains( & VarIndex::new(7 << 8) ) ) ;
+/// }
+/// ```
+///
+/// The whole API is inherent, the underlying `HashSet` is not exposed. The iterators it returns
+/// have nameable aliases such as [`IntHSetIter`](type.IntHSetIter.html).
+///
+/// Set operations return `IntHSet`s.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+/// }
+/// fn main() {
+///     let lft: VarSet = (0..4).map(VarIndex::new).collect() ;
+///     let rgt: VarSet = (2..6).map(VarIndex::new).collect() ;
+///     let expected: VarSet = (0..6).map(VarIndex::new).collect() ;
+///     assert_eq!( & lft | & rgt, expected ) ;
+///     let expected: VarSet = (2..4).map(VarIndex::new).collect() ;
+///     assert_eq!( & lft & & rgt, expected ) ;
+///     let expected: VarSet = (0..2).map(VarIndex::new).collect() ;
+///     assert_eq!( & lft - & rgt, expected ) ;
+///     let mut sym = lft.clone() ;
+///     sym.symmetric_difference_with(& rgt) ;
+///     assert_eq!( sym, & (& lft | & rgt) - & (& lft & & rgt) ) ;
+///     assert!( ( & lft & & rgt ).is_subset(& lft) ) ;
+///     assert!( ( & lft - & rgt ).is_disjoint(& rgt) ) ;
+///     let union: Vec<_> = (& lft | & rgt).into_sorted_vec() ;
+///     assert_eq!( union, (0..6).map(VarIndex::new).collect::<Vec<_>>() ) ;
+/// }
+/// ```
+///
+/// Hashing and ordering only depend on the elements, so sets can be keys of hash maps and
+/// `BTreeMap`s.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use std::collections::{ BTreeMap, HashMap } ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+/// }
+/// fn main() {
+///     let lft: VarSet = (0..100).map(VarIndex::new).collect() ;
+///     let mut rgt = VarSet::new() ;
+///     rgt.extend( (0..100).rev().map(VarIndex::new) ) ;
+///     rgt.insert( VarIndex::new(100) ) ;
+///     rgt.remove( & VarIndex::new(100) ) ;
+///     rgt.shrink_to_fit() ;
+///
+///     let mut map = HashMap::new() ;
+///     map.insert(lft.clone(), "lft") ;
+///     assert_eq!( map.get(& rgt), Some(& "lft") ) ;
+///
+///     let small: VarSet = (0..2).map(VarIndex::new).collect() ;
+///     let mut tree = BTreeMap::new() ;
+///     tree.insert(lft, "lft") ;
+///     tree.insert(small.clone(), "small") ;
+///     assert_eq!( tree.keys().next(), Some(& small) ) ;
+///     assert_eq!( tree.get(& rgt), Some(& "lft") ) ;
+/// }
+/// ```
+pub struct IntHSet<Int: IntHash, S = BuildHashUsize> {
+    set: HashSet<Int, S>,
+}
+impl<Int: IntHash + Clone, S: Clone> Clone for IntHSet<Int, S> {
+    fn clone(&self) -> Self {
+        IntHSet {
+            set: self.set.clone(),
+        }
+    }
+}
+impl<Int: IntHash + ::std::fmt::Debug, S> ::std::fmt::Debug for IntHSet<Int, S> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_struct("IntHSet").field("set", &self.set).finish()
+    }
+}
+impl<Int: IntHash, S: BuildHasher> PartialEq for IntHSet<Int, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.set.eq(&other.set)
+    }
+}
+impl<Int: IntHash, S: BuildHasher> Eq for IntHSet<Int, S> {}
+impl<Int: IntHash, S> Hash for IntHSet<Int, S> {
+    /// Independent of the iteration order, consistent with `PartialEq`.
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: ::std::hash::Hasher,
+    {
+        state.write_u64(unordered_hash(self.set.iter()))
+    }
+}
+impl<Int: IntHash + Ord, S: BuildHasher> PartialOrd for IntHSet<Int, S> {
+    /// Compares the sorted elements, lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<Int: IntHash + Ord, S: BuildHasher> Ord for IntHSet<Int, S> {
+    /// Compares the sorted elements, lexicographically.
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.iter_sorted().cmp(other.iter_sorted())
+    }
+}
+impl<Int: IntHash, S: BuildHasher + Default> Default for IntHSet<Int, S> {
+    fn default() -> Self {
+        IntHSet {
+            set: HashSet::default(),
+        }
+    }
+}
+impl<Int: IntHash, S: BuildHasher + Default> IntHSet<Int, S> {
+    /// Empty hash set.
+    pub fn new() -> IntHSet<Int, S> {
+        IntHSet {
+            set: HashSet::with_hasher(S::default()),
+        }
+    }
+    /// Empty hash set with some capacity.
+    pub fn with_capacity(capa: usize) -> IntHSet<Int, S> {
+        IntHSet {
+            set: HashSet::with_capacity_and_hasher(capa, S::default()),
+        }
+    }
+}
+impl<Int: IntHash, S> IntHSet<Int, S> {
+    /// An iterator visiting all elements.
+    #[inline]
+    pub fn iter(&self) -> IntHSetIter<'_, Int> {
+        self.set.iter()
+    }
+    /// Number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+    /// True if the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+    /// Number of elements the set can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.set.capacity()
+    }
+    /// Removes all the elements.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set.clear()
+    }
+    /// Removes all the elements and iterates over them.
+    #[inline]
+    pub fn drain(&mut self) -> IntHSetDrain<'_, Int> {
+        self.set.drain()
+    }
+    /// Only keeps the elements verifying a predicate.
+    #[inline]
+    pub fn retain<F: FnMut(&Int) -> bool>(&mut self, keep: F) {
+        self.set.retain(keep)
+    }
+    /// The hasher of the set.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.set.hasher()
+    }
+}
+impl<Int: IntHash + Ord, S> IntHSet<Int, S> {
+    /// Iterates over the elements in increasing order.
+    ///
+    /// Does not allocate if the hash table already yields the elements in increasing order, which
+    /// is usually the case with the trivial hasher when the elements are smaller than the
+    /// capacity. Otherwise, sorts a vector of references to the elements: use
+    /// [`into_sorted_vec`](#method.into_sorted_vec) if the set is not needed afterwards.
+    pub fn iter_sorted(&self) -> IntHSetSortedIter<'_, Int> {
+        let iter = if self.set.iter().is_sorted() {
+            SortedIter::InOrder(self.set.iter())
+        } else {
+            let mut elems: Vec<_> = self.set.iter().collect();
+            elems.sort_unstable();
+            SortedIter::Sorted(elems.into_iter())
+        };
+        IntHSetSortedIter { iter }
+    }
+    /// The elements in increasing order.
+    pub fn into_sorted_vec(self) -> Vec<Int> {
+        let mut elems: Vec<_> = self.set.into_iter().collect();
+        elems.sort_unstable();
+        elems
+    }
+}
+impl<Int: IntHash, S: BuildHasher> IntHSet<Int, S> {
+    /// True if the set contains an element.
+    #[inline]
+    pub fn contains(&self, elem: &Int) -> bool {
+        self.set.contains(elem)
+    }
+    /// Reference to the element of the set equal to `elem`, if any.
+    #[inline]
+    pub fn get(&self, elem: &Int) -> Option<&Int> {
+        self.set.get(elem)
+    }
+    /// Inserts an element, returns `true` if it was not there.
+    #[inline]
+    pub fn insert(&mut self, elem: Int) -> bool {
+        self.set.insert(elem)
+    }
+    /// Inserts an element, returns the element equal to it that it replaced, if any.
+    #[inline]
+    pub fn replace(&mut self, elem: Int) -> Option<Int> {
+        self.set.replace(elem)
+    }
+    /// Removes an element, returns `true` if it was there.
+    #[inline]
+    pub fn remove(&mut self, elem: &Int) -> bool {
+        self.set.remove(elem)
+    }
+    /// Removes an element and returns it, if it was there.
+    #[inline]
+    pub fn take(&mut self, elem: &Int) -> Option<Int> {
+        self.set.take(elem)
+    }
+    /// Reference to the element of the set equal to `elem`, inserts the result of `f` applied to
+    /// `elem` if there is none.
+    pub fn get_or_insert_with<F: FnOnce(&Int) -> Int>(&mut self, elem: &Int, f: F) -> &Int {
+        if !self.set.contains(elem) {
+            self.set.insert(f(elem));
+        }
+        self.set
+            .get(elem)
+            .expect("[illegal] `get_or_insert_with` produced an element different from its input")
+    }
+    /// Reserves room for at least `additional` more elements.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.set.reserve(additional)
+    }
+    /// Shrinks the capacity of the set as much as possible.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.set.shrink_to_fit()
+    }
+}
+impl<'a, Int, S> IntoIterator for &'a IntHSet<Int, S>
+where
+    Int: IntHash,
+{
+    type Item = &'a Int;
+    type IntoIter = IntHSetIter<'a, Int>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.iter()
+    }
+}
+impl<Int, S> IntoIterator for IntHSet<Int, S>
+where
+    Int: IntHash,
+{
+    type Item = Int;
+    type IntoIter = IntHSetIntoIter<Int>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.into_iter()
+    }
+}
+impl<Int, S> ::std::iter::FromIterator<Int> for IntHSet<Int, S>
+where
+    Int: IntHash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = Int>>(iter: I) -> Self {
+        IntHSet {
+            set: HashSet::from_iter(iter),
+        }
+    }
+}
+impl<Int, S> ::std::iter::Extend<Int> for IntHSet<Int, S>
+where
+    Int: IntHash,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = Int>>(&mut self, iter: I) {
+        self.set.extend(iter)
+    }
+}
+impl<'a, Int, S> ::std::iter::Extend<&'a Int> for IntHSet<Int, S>
+where
+    Int: 'a + IntHash + Copy,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = &'a Int>>(&mut self, iter: I) {
+        self.set.extend(iter)
+    }
+}
+impl<Int: IntHash + Clone, S: BuildHasher> IntHSet<Int, S> {
+    /// Adds all the elements of `other` to `self`.
+    pub fn union_with(&mut self, other: &Self) {
+        self.set.extend(other.set.iter().cloned())
+    }
+    /// Removes the elements of `self` that are not in `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.set.retain(|elem| other.set.contains(elem))
+    }
+    /// Removes the elements of `other` from `self`.
+    pub fn difference_with(&mut self, other: &Self) {
+        if other.len() < self.len() {
+            for elem in &other.set {
+                self.set.remove(elem);
+            }
+        } else {
+            self.set.retain(|elem| !other.set.contains(elem))
+        }
+    }
+    /// Keeps the elements that are in exactly one of `self` and `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        for elem in &other.set {
+            if !self.set.remove(elem) {
+                self.set.insert(elem.clone());
+            }
+        }
+    }
+
+    /// True if all the elements of `self` are in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.set.iter().all(|elem| other.set.contains(elem))
+    }
+    /// True if all the elements of `other` are in `self`.
+    #[inline]
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+    /// True if `self` and `other` have no element in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small.set.iter().all(|elem| !big.set.contains(elem))
+    }
+}
+impl<'a, Int, S> ::std::ops::BitOr<&'a IntHSet<Int, S>> for &'a IntHSet<Int, S>
+where
+    Int: IntHash + Clone,
+    S: BuildHasher + Clone,
+{
+    type Output = IntHSet<Int, S>;
+    /// Union.
+    fn bitor(self, other: &'a IntHSet<Int, S>) -> IntHSet<Int, S> {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut res = big.clone();
+        res.union_with(small);
+        res
+    }
+}
+impl<'a, Int, S> ::std::ops::BitAnd<&'a IntHSet<Int, S>> for &'a IntHSet<Int, S>
+where
+    Int: IntHash + Clone,
+    S: BuildHasher + Clone,
+{
+    type Output = IntHSet<Int, S>;
+    /// Intersection.
+    fn bitand(self, other: &'a IntHSet<Int, S>) -> IntHSet<Int, S> {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut set = HashSet::with_capacity_and_hasher(small.len(), small.hasher().clone());
+        set.extend(small.iter().filter(|elem| big.contains(*elem)).cloned());
+        IntHSet { set }
+    }
+}
+impl<'a, Int, S> ::std::ops::BitXor<&'a IntHSet<Int, S>> for &'a IntHSet<Int, S>
+where
+    Int: IntHash + Clone,
+    S: BuildHasher + Clone,
+{
+    type Output = IntHSet<Int, S>;
+    /// Symmetric difference.
+    fn bitxor(self, other: &'a IntHSet<Int, S>) -> IntHSet<Int, S> {
+        let mut res = self.clone();
+        res.symmetric_difference_with(other);
+        res
+    }
+}
+impl<'a, Int, S> ::std::ops::Sub<&'a IntHSet<Int, S>> for &'a IntHSet<Int, S>
+where
+    Int: IntHash + Clone,
+    S: BuildHasher + Clone,
+{
+    type Output = IntHSet<Int, S>;
+    /// Difference.
+    fn sub(self, other: &'a IntHSet<Int, S>) -> IntHSet<Int, S> {
+        let mut set = HashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        set.extend(self.iter().filter(|elem| !other.contains(*elem)).cloned());
+        IntHSet { set }
+    }
+}
+
+/// Wraps a hash map with a trivial hasher.
+///
+/// The hasher `S` defaults to the trivial one, see the [`hash`](hash/index.html) module for the
+/// alternatives.
+///
+/// The whole API is inherent, the underlying `HashMap` is not exposed. The iterators it returns
+/// have nameable aliases such as [`IntHMapIter`](type.IntHMapIter.html) or
+/// [`IntHMapEntry`](type.IntHMapEntry.html).
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::{ IntHMapEntry, IntHMapKeys } ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Map of variable indices."]
+///     hash map: VarHMap
+/// }
+/// fn occurrence(map: & mut VarHMap<usize>, var: VarIndex) -> IntHMapEntry<VarIndex, usize> {
+///     map.entry(var)
+/// }
+/// fn vars(map: & VarHMap<usize>) -> IntHMapKeys<VarIndex, usize> {
+///     map.keys()
+/// }
+/// fn main() {
+///     let mut map = VarHMap::new() ;
+///     * occurrence(& mut map, VarIndex::new(3)).or_insert(0) += 1 ;
+///     * occurrence(& mut map, VarIndex::new(3)).or_insert(0) += 1 ;
+///     * map.get_or_insert_with( VarIndex::new(4), || 7 ) += 1 ;
+///     assert_eq!( vars(& map).count(), 2 ) ;
+///     let sorted: Vec<_> = map.iter_sorted().map( |(var, _)| var.get() ).collect() ;
+///     assert_eq!( sorted, vec![3, 4] ) ;
+///     map.retain( |_, count| * count > 2 ) ;
+///     let drained: Vec<_> = map.drain().collect() ;
+///     assert_eq!( drained, vec![ (VarIndex::new(4), 8) ] ) ;
+///     assert!( map.is_empty() ) ;
+/// }
+/// ```
+///
+/// Operations on the key set take an [`IntHSet`](struct.IntHSet.html) or another `IntHMap`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+///     #[doc = "Map of variable indices."]
+///     hash map: VarHMap
+/// }
+/// fn main() {
+///     let mut map: VarHMap<& str> = (0..4).map(|n| (VarIndex::new(n), "x")).collect() ;
+///     let live: VarSet = (1..3).map(VarIndex::new).collect() ;
+///     assert!( live.is_subset( & map.key_set() ) ) ;
+///     map.intersect_keys_with(& live) ;
+///     assert_eq!( map.key_set(), live ) ;
+///     map.difference_keys_with(& live) ;
+///     assert!( map.is_empty() ) ;
+/// }
+/// ```
+pub struct IntHMap<Int: IntHash, V, S = BuildHashUsize> {
+    map: HashMap<Int, V, S>,
+}
+impl<Int: IntHash + Clone, V: Clone, S: Clone> Clone for IntHMap<Int, V, S> {
+    fn clone(&self) -> Self {
+        IntHMap {
+            map: self.map.clone(),
+        }
+    }
+}
+impl<Int, V, S> ::std::fmt::Debug for IntHMap<Int, V, S>
+where
+    Int: IntHash + ::std::fmt::Debug,
+    V: ::std::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_struct("IntHMap").field("map", &self.map).finish()
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher + Default> Default for IntHMap<Int, V, S> {
+    fn default() -> Self {
+        IntHMap {
+            map: HashMap::default(),
+        }
+    }
+}
+impl<Int: IntHash, V: PartialEq, S: BuildHasher> PartialEq for IntHMap<Int, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map.eq(&other.map)
+    }
+}
+impl<Int: IntHash, V: Eq, S: BuildHasher> Eq for IntHMap<Int, V, S> {}
+impl<Int: IntHash, V: Hash, S> Hash for IntHMap<Int, V, S> {
+    /// Independent of the iteration order, consistent with `PartialEq`.
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: ::std::hash::Hasher,
+    {
+        state.write_u64(unordered_hash(self.map.iter()))
+    }
+}
+impl<Int: IntHash + Ord, V: PartialOrd, S: BuildHasher> PartialOrd for IntHMap<Int, V, S> {
+    /// Compares the bindings sorted by keys, lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        self.iter_sorted().partial_cmp(other.iter_sorted())
+    }
+}
+impl<Int: IntHash + Ord, V: Ord, S: BuildHasher> Ord for IntHMap<Int, V, S> {
+    /// Compares the bindings sorted by keys, lexicographically.
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.iter_sorted().cmp(other.iter_sorted())
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher + Default> IntHMap<Int, V, S> {
+    /// Empty hash map.
+    pub fn new() -> IntHMap<Int, V, S> {
+        IntHMap {
+            map: HashMap::with_hasher(S::default()),
+        }
+    }
+    /// Empty hash map with some capacity.
+    pub fn with_capacity(capa: usize) -> IntHMap<Int, V, S> {
+        IntHMap {
+            map: HashMap::with_capacity_and_hasher(capa, S::default()),
+        }
+    }
+}
+impl<Int: IntHash, V, S> IntHMap<Int, V, S> {
+    /// An iterator visiting all elements.
+    #[inline]
+    pub fn iter(&self) -> IntHMapIter<'_, Int, V> {
+        self.map.iter()
+    }
+    /// An iterator visiting all elements.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IntHMapIterMut<'_, Int, V> {
+        self.map.iter_mut()
+    }
+    /// Iterates over the keys.
+    #[inline]
+    pub fn keys(&self) -> IntHMapKeys<'_, Int, V> {
+        self.map.keys()
+    }
+    /// Iterates over the values.
+    #[inline]
+    pub fn values(&self) -> IntHMapValues<'_, Int, V> {
+        self.map.values()
+    }
+    /// Iterates over the values, mutable version.
+    #[inline]
+    pub fn values_mut(&mut self) -> IntHMapValuesMut<'_, Int, V> {
+        self.map.values_mut()
+    }
+    /// Number of bindings.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+    /// True if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+    /// Number of bindings the map can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+    /// Removes all the bindings.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+    /// Removes all the bindings and iterates over them.
+    #[inline]
+    pub fn drain(&mut self) -> IntHMapDrain<'_, Int, V> {
+        self.map.drain()
+    }
+    /// Only keeps the bindings verifying a predicate.
+    #[inline]
+    pub fn retain<F: FnMut(&Int, &mut V) -> bool>(&mut self, keep: F) {
+        self.map.retain(keep)
+    }
+    /// The hasher of the map.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+}
+impl<Int: IntHash + Ord, V, S> IntHMap<Int, V, S> {
+    /// Iterates over the bindings in increasing order of keys.
+    ///
+    /// Does not allocate if the hash table already yields the keys in increasing order, which is
+    /// usually the case with the trivial hasher when the keys are smaller than the capacity.
+    /// Otherwise, sorts a vector of references to the bindings: use
+    /// [`into_sorted_vec`](#method.into_sorted_vec) if the map is not needed afterwards.
+    pub fn iter_sorted(&self) -> IntHMapSortedIter<'_, Int, V> {
+        let iter = if self.map.keys().is_sorted() {
+            SortedIter::InOrder(self.map.iter())
+        } else {
+            let mut bindings: Vec<_> = self.map.iter().collect();
+            bindings.sort_unstable_by_key(|(key, _)| *key);
+            SortedIter::Sorted(bindings.into_iter())
+        };
+        IntHMapSortedIter { iter }
+    }
+    /// The bindings in increasing order of keys.
+    pub fn into_sorted_vec(self) -> Vec<(Int, V)> {
+        let mut bindings: Vec<_> = self.map.into_iter().collect();
+        bindings.sort_unstable_by(|(k_1, _), (k_2, _)| k_1.cmp(k_2));
+        bindings
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher> IntHMap<Int, V, S> {
+    /// True if a key is bound.
+    #[inline]
+    pub fn contains_key(&self, key: &Int) -> bool {
+        self.map.contains_key(key)
+    }
+    /// Value of a key, if any.
+    #[inline]
+    pub fn get(&self, key: &Int) -> Option<&V> {
+        self.map.get(key)
+    }
+    /// Key and value of a key, if any.
+    #[inline]
+    pub fn get_key_value(&self, key: &Int) -> Option<(&Int, &V)> {
+        self.map.get_key_value(key)
+    }
+    /// Value of a key, mutable version.
+    #[inline]
+    pub fn get_mut(&mut self, key: &Int) -> Option<&mut V> {
+        self.map.get_mut(key)
+    }
+    /// Inserts a binding, returns the previous value of the key, if any.
+    #[inline]
+    pub fn insert(&mut self, key: Int, val: V) -> Option<V> {
+        self.map.insert(key, val)
+    }
+    /// Removes a binding, returns its value if any.
+    #[inline]
+    pub fn remove(&mut self, key: &Int) -> Option<V> {
+        self.map.remove(key)
+    }
+    /// Removes a binding, returns it if any.
+    #[inline]
+    pub fn remove_entry(&mut self, key: &Int) -> Option<(Int, V)> {
+        self.map.remove_entry(key)
+    }
+    /// Entry of a key, for in-place manipulation.
+    #[inline]
+    pub fn entry(&mut self, key: Int) -> IntHMapEntry<'_, Int, V> {
+        self.map.entry(key)
+    }
+    /// Value of a key, binds it to the result of `f` if it is not bound.
+    #[inline]
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: Int, f: F) -> &mut V {
+        self.map.entry(key).or_insert_with(f)
+    }
+    /// Reserves room for at least `additional` more bindings.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+    /// Shrinks the capacity of the map as much as possible.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+}
+impl<'a, Int, V, S> IntoIterator for &'a IntHMap<Int, V, S>
+where
+    Int: IntHash,
+{
+    type Item = (&'a Int, &'a V);
+    type IntoIter = IntHMapIter<'a, Int, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+impl<'a, Int, V, S> IntoIterator for &'a mut IntHMap<Int, V, S>
+where
+    Int: IntHash,
+{
+    type Item = (&'a Int, &'a mut V);
+    type IntoIter = IntHMapIterMut<'a, Int, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut()
+    }
+}
+impl<Int, V, S> IntoIterator for IntHMap<Int, V, S>
+where
+    Int: IntHash,
+{
+    type Item = (Int, V);
+    type IntoIter = IntHMapIntoIter<Int, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+impl<Int, V, S> ::std::iter::FromIterator<(Int, V)> for IntHMap<Int, V, S>
+where
+    Int: IntHash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (Int, V)>>(iter: I) -> Self {
+        IntHMap {
+            map: HashMap::from_iter(iter),
+        }
+    }
+}
+impl<Int, V, S> ::std::iter::Extend<(Int, V)> for IntHMap<Int, V, S>
+where
+    Int: IntHash,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (Int, V)>>(&mut self, iter: I) {
+        self.map.extend(iter)
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher> IntHMap<Int, V, S> {
+    /// The set of keys of the map.
+    pub fn key_set(&self) -> IntHSet<Int, S>
+    where
+        Int: Clone,
+        S: Clone,
+    {
+        let mut set = HashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+        set.extend(self.keys().cloned());
+        IntHSet { set }
+    }
+    /// Removes the bindings of the keys that are not in `keys`.
+    pub fn intersect_keys_with(&mut self, keys: &IntHSet<Int, S>) {
+        self.map.retain(|key, _| keys.set.contains(key))
+    }
+    /// Removes the bindings of the keys that are in `keys`.
+    pub fn difference_keys_with(&mut self, keys: &IntHSet<Int, S>) {
+        if keys.len() < self.len() {
+            for key in &keys.set {
+                self.map.remove(key);
+            }
+        } else {
+            self.map.retain(|key, _| !keys.set.contains(key))
+        }
+    }
+    /// True if all the keys of `self` are keys of `other`.
+    pub fn is_key_subset<W>(&self, other: &IntHMap<Int, W, S>) -> bool {
+        self.len() <= other.len() && self.map.keys().all(|key| other.map.contains_key(key))
+    }
+    /// True if `self` and `other` have no key in common.
+    pub fn is_key_disjoint<W>(&self, other: &IntHMap<Int, W, S>) -> bool {
+        if self.len() <= other.len() {
+            self.map.keys().all(|key| !other.map.contains_key(key))
+        } else {
+            other.map.keys().all(|key| !self.map.contains_key(key))
+        }
+    }
+}
+
+/// Wraps a `usize` into a struct (zero-cost). Also generates the relevant collections indexed by
+/// the wrapper.
+///
+/// - implements `From` for `usize`, and `Deref` to the backing integer,
+/// - implements `Debug`, `Clone`, `Copy`, `PartialOrd`, `Ord`, `PartialEq`,
+///   `Eq`, `Hash` and `Display`.
+///
+/// Can also generate a range structure allowing to iterate over a range of indices.
+///
+/// For more details see [the example](safe/int/examples/index.html).
+///
+/// # Usage
+///
+/// Most basic use-case is simply to wrap something:
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// # fn main() {}
+/// wrap_usize!{
+///     #[doc = "Arity."]
+///     Arity
+/// }
+/// ```
+///
+/// # Backing integer
+///
+/// The wrapper stores a `usize` by default. Writing `repr: <int>` right after the wrapper
+/// identifier makes it store a `u8`, `u16`, `u32` or `u64` instead, while `IntWrap::inner` and
+/// `get` still return a `usize`. The backing integer should not be wider than `usize`.
+///
+/// `new` and `From<usize>` check for overflow in debug only; `try_new` is the fallible version.
+///
+/// Writing `repr: <int> with niche` stores the value plus one in a `NonZero<int>`, so that
+/// `Option<Id>` has the same size as `Id`. The maximum value of the backing integer cannot be
+/// wrapped, and `Id` does not implement `Deref` in this mode.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex repr: u32
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// fn main() {
+///     use std::mem::size_of ;
+///     assert_eq!( size_of::<VarIndex>(), 4 ) ;
+///     let mut idx = VarIndex::new(41) ;
+///     idx.inc() ;
+///     assert_eq!( idx.get(), 42 ) ;
+///     assert_eq!( * idx, 42u32 ) ;
+///     assert_eq!( VarIndex::try_new(7), Some( VarIndex::new(7) ) ) ;
+///     assert_eq!( VarIndex::try_new(1 << 40), None ) ;
+///
+///     let map: VarMap<VarIndex> = vec![ idx ; 3 ].into() ;
+///     assert_eq!( map[ VarIndex::one() ], 42 ) ;
+/// }
+/// ```
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex repr: u32 with niche
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// fn main() {
+///     use std::mem::size_of ;
+///     assert_eq!( size_of::<VarIndex>(), size_of::<u32>() ) ;
+///     assert_eq!( size_of::<Option<VarIndex>>(), size_of::<VarIndex>() ) ;
+///     // Parent/link tables use one `u32` per element.
+///     assert_eq!( size_of::<[Option<VarIndex> ; 16]>(), 16 * size_of::<u32>() ) ;
+///     assert_eq!( VarIndex::zero().get(), 0 ) ;
+///     assert_eq!( VarIndex::try_new( u32::MAX as usize ), None ) ;
+///
+///     let mut parent: VarMap<Option<VarIndex>> = vec![ None ; 3 ].into() ;
+///     parent[ VarIndex::new(2) ] = Some( VarIndex::zero() ) ;
+///     let set: VarSet = parent.iter().filter_map(|p| * p).collect() ;
+///     assert!( set.contains( & VarIndex::zero() ) ) ;
+///     assert_eq!( format!("{:?}", VarIndex::one()), "VarIndex { val: 1 }" ) ;
+/// }
+/// ```
+///
+/// # Tags
+///
+/// After the mandatory comment and wrapper identifier `Id`, one can add any combination of the
+/// following tags using the syntax `#[doc = <comment>] <tag>: <ident>` (see example below):
+///
+/// - `range`: alias type for an [`IntRange`](safe/int/struct.IntRange.html), to iterate between
+///   two `Id`s or slice a `map`,
+/// - `set`: alias type for a set `Id`s with 0-cost hashing,
+/// - `small set`: alias type for an [`IntSmallSet`](safe/int/struct.IntSmallSet.html), a `set`
+///   storing up to 8 `Id`s inline before allocating a hash table,
+/// - `bitset`: alias type for a dense set of `Id`s storing one bit per index,
+/// - `sparse set`: alias type for an [`IntSparseSet`](safe/int/struct.IntSparseSet.html), a set
+///   of `Id`s with constant-time clear iterating in insertion order,
+/// - `hash map`: alias type for a hash map from `Id` to something with 0-cost hashing,
+/// - `map`: alias type for an [`IndexVec`](safe/int/struct.IndexVec.html), a wrapper around a
+///   vector forcing to use `Id` instead of `usize` to access elements, and for its
+///   [`IndexIter`](safe/int/struct.IndexIter.html) iterator (`with iter`),
+/// - `tagged map`: alias type for a [`TaggedVec`](safe/int/struct.TaggedVec.html), a `map` that
+///   rejects, in debug, indices coming from another instance,
+/// - `interner`: alias type for an [`Interner`](safe/int/struct.Interner.html), a bijection
+///   between values and `Id`s,
+/// - `slab`: alias type for an [`IntSlab`](safe/int/struct.IntSlab.html), a vector recycling the
+///   `Id`s of removed elements,
+/// - `gen slab`: same as `slab` but with keys detecting when they are used after their element
+///   was removed,
+/// - `union find`: alias type for a [`UnionFind`](safe/int/struct.UnionFind.html), equivalence
+///   classes of `Id`s,
+/// - `graph`: alias type for a [`Graph`](safe/graph/struct.Graph.html), a directed graph whose
+///   nodes are `Id`s.
+///
+/// # Examples
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// # fn main() {}
+/// wrap_usize!{
+///     #[doc = "Index of a non-terminal."]
+///     NtIndex
+///     #[doc = "Range over `NtIndex`."]
+///     range: NtRange
+///     #[doc = "Set of non-terminal indices."]
+///     set: NtSet
+///     #[doc = "Dense set of non-terminal indices."]
+///     bitset: NtBitSet
+///     #[doc = "Map of non-terminal indices."]
+///     hash map: NtHMap
+///     #[doc = "Vector indexed by non-terminal indices."]
+///     map: NtMap with iter: NtMapIter
+///     #[doc = "Interning table for values indexed by non-terminal indices."]
+///     interner: NtTable
+/// }
+/// ```
+#[macro_export]
+macro_rules! wrap_usize {
+  // // Vector (internal).
+  // ( internal $t:ident #[$cmt:meta] vec: $vec:ident $($tail:tt)* ) => (
+  //   #[$cmt]
+  //   pub type $vec = Vec<$t> ;
+  //   wrap_usize!{ internal $t $($tail)* }
+  // ) ;
+
+    // Set (internal).
+    ( |internal| $t:ident #[$cmt:meta] set: $set:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $set = $crate::safe::int::IntHSet<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Small set (internal).
+    ( |internal| $t:ident #[$cmt:meta] small set: $set:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $set = $crate::safe::int::IntSmallSet<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Bit set (internal).
+    ( |internal| $t:ident #[$cmt:meta] bitset: $set:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $set = $crate::safe::int::IntBitSet<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Sparse set (internal).
+    ( |internal| $t:ident #[$cmt:meta] sparse set: $set:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $set = $crate::safe::int::IntSparseSet<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Hash map (internal).
+    ( |internal| $t:ident #[$cmt:meta] hash map: $map:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $map<T> = $crate::safe::int::IntHMap<$t, T> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Map with provenance checks in debug (internal).
+    ( |internal| $t:ident #[$cmt:meta] tagged map: $map:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $map<T> = $crate::safe::int::TaggedVec<$t, T> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Interning table (internal).
+    ( |internal| $t:ident #[$cmt:meta] interner: $table:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $table<T> = $crate::safe::int::Interner<$t, T> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Slab (internal).
+    ( |internal| $t:ident #[$cmt:meta] slab: $slab:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $slab<T> = $crate::safe::int::IntSlab<$t, T> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Slab with generation checks (internal).
+    ( |internal| $t:ident #[$cmt:meta] gen slab: $slab:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $slab<T> = $crate::safe::int::IntSlab<
+            $crate::safe::int::GenIndex<$t>, T
+        > ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Union-find (internal).
+    ( |internal| $t:ident #[$cmt:meta] union find: $uf:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $uf = $crate::safe::int::UnionFind<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Graph (internal).
+    ( |internal| $t:ident #[$cmt:meta] graph: $graph:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $graph = $crate::safe::graph::Graph<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Range (internal).
+    ( |internal| $t:ident #[$cmt:meta] range: $range:ident $($tail:tt)* ) => (
+        #[$cmt]
+        pub type $range = $crate::safe::int::IntRange<$t> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Map: vector indexed by `$t` (internal).
+    (
+        |internal| $t:ident #[$cmt:meta]
+        map: $map:ident with iter: $iter:ident
+        $($tail:tt)*
+    ) => (
+        #[$cmt]
+        pub type $map<T> = $crate::safe::int::IndexVec<$t, T> ;
+        /// Iterator over the elements of a map and their index.
+        pub type $iter<It> = $crate::safe::int::IndexIter<$t, It> ;
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Terminal case (internal).
+    ( |internal| $t:ident ) => () ;
+
+    // Wrapper storing a `$int` (internal).
+    ( |wrapper| #[$cmt:meta] $t:ident repr: $int:ident ) => (
+        #[$cmt]
+        #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+        pub struct $t {
+            val: $int
+        }
+        impl $t {
+            /// Wraps an int.
+            ///
+            /// In debug, panics if `val` does not fit in the backing integer.
+            #[inline]
+            pub fn new(val: usize) -> Self {
+                debug_assert! {
+                    <$int as ::std::convert::TryFrom<usize>>::try_from(val).is_ok(),
+                    "[illegal] `{}::new({})`: value does not fit in a `{}`",
+                    stringify!($t), val, stringify!($int)
+                }
+                $t { val: val as $int }
+            }
+            /// Wraps an int, `None` if it does not fit in the backing integer.
+            #[inline]
+            pub fn try_new(val: usize) -> Option<Self> {
+                <$int as ::std::convert::TryFrom<usize>>::try_from(val).ok().map(
+                    |val| $t { val }
+                )
+            }
+            /// Zero.
+            #[inline]
+            pub fn zero() -> Self {
+                $t { val: 0 }
+            }
+            /// One.
+            #[inline]
+            pub fn one() -> Self {
+                $t { val: 1 }
+            }
+            /// Accessor.
+            #[inline]
+            pub fn get(& self) -> usize {
+                self.val as usize
+            }
+            /// Increments the int.
+            #[inline]
+            pub fn inc(& mut self) {
+                self.val += 1
+            }
+            /// Decrements the int.
+            #[inline]
+            pub fn dec(& mut self) {
+                self.val -= 1
+            }
+        }
+        impl ::std::ops::Deref for $t {
+            type Target = $int ;
+            #[inline]
+            fn deref(& self) -> & $int {
+                & self.val
+            }
+        }
+    ) ;
+
+    // Wrapper storing a non-zero `$int`, `usize::MAX` is reserved (internal).
+    ( |wrapper| #[$cmt:meta] $t:ident repr: $int:ident with niche ) => (
+        #[$cmt]
+        #[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+        pub struct $t {
+            /// Wrapped value plus one.
+            val: ::std::num::NonZero<$int>
+        }
+        impl $t {
+            /// Wraps an int.
+            ///
+            /// In debug, panics if `val` does not fit in the backing integer, or is its maximum
+            /// value.
+            #[inline]
+            pub fn new(val: usize) -> Self {
+                debug_assert! {
+                    Self::try_new(val).is_some(),
+                    "[illegal] `{}::new({})`: value does not fit in a `{}` minus its maximum",
+                    stringify!($t), val, stringify!($int)
+                }
+                $t {
+                    val: ::std::num::NonZero::new(
+                        (val as $int).wrapping_add(1)
+                    ).expect("[illegal] wrapping the maximum value of a niche-optimized index")
+                }
+            }
+            /// Wraps an int, `None` if it does not fit in the backing integer or is its maximum
+            /// value.
+            #[inline]
+            pub fn try_new(val: usize) -> Option<Self> {
+                <$int as ::std::convert::TryFrom<usize>>::try_from(val).ok().and_then(
+                    |val| val.checked_add(1)
+                ).and_then(
+                    ::std::num::NonZero::new
+                ).map(
+                    |val| $t { val }
+                )
+            }
+            /// Zero.
+            #[inline]
+            pub fn zero() -> Self {
+                $t { val: ::std::num::NonZero::<$int>::MIN }
+            }
+            /// One.
+            #[inline]
+            pub fn one() -> Self {
+                $t::new(1)
+            }
+            /// Accessor.
+            #[inline]
+            pub fn get(& self) -> usize {
+                (self.val.get() - 1) as usize
+            }
+            /// Increments the int.
+            #[inline]
+            pub fn inc(& mut self) {
+                * self = $t::new( self.get() + 1 )
+            }
+            /// Decrements the int.
+            #[inline]
+            pub fn dec(& mut self) {
+                self.val = ::std::num::NonZero::new(
+                    self.val.get() - 1
+                ).expect("[illegal] decrementing zero")
+            }
+        }
+        impl ::std::fmt::Debug for $t {
+            fn fmt(& self, fmt: & mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                fmt.debug_struct( stringify!($t) ).field( "val", & self.get() ).finish()
+            }
+        }
+    ) ;
+
+    // Everything that only relies on `new` and `get` (internal).
+    ( |common| $t:ident ) => (
+        impl $crate::safe::int::IntWrap for $t {
+            fn inner(& self) -> usize { self.get() }
+        }
+        impl $crate::safe::int::IntHash for $t {}
+        impl ::std::hash::Hash for $t {
+            #[inline]
+            fn hash<H: ::std::hash::Hasher>(& self, state: & mut H) {
+                state.write_usize( self.get() )
+            }
+        }
+        impl ::std::convert::From<usize> for $t {
+            #[inline]
+            fn from(val: usize) -> Self {
+                $t::new(val)
+            }
+        }
+        impl<'a> ::std::convert::From<& 'a usize> for $t {
+            #[inline]
+            fn from(val: & 'a usize) -> Self {
+                $t::new(* val)
+            }
+        }
+        impl ::std::convert::From<$t> for usize {
+            #[inline]
+            fn from(val: $t) -> usize {
+                val.get()
+            }
+        }
+        impl<'a> ::std::convert::From<& 'a $t> for usize {
+            #[inline]
+            fn from(val: & 'a $t) -> usize {
+                val.get()
+            }
+        }
+        impl<T: ::std::convert::Into<usize>> ::std::ops::AddAssign<T> for $t {
+            #[inline]
+            fn add_assign(& mut self, rhs: T) {
+                * self = $t::new( self.get() + rhs.into() )
+            }
+        }
+        impl<T: ::std::convert::Into<usize>> ::std::ops::Add<T> for $t {
+            type Output = $t ;
+            #[inline]
+            fn add(self, rhs: T) -> $t {
+                $t::new( self.get() + rhs.into() )
+            }
+        }
+        impl ::std::fmt::Display for $t {
+            #[inline]
+            fn fmt(& self, fmt: & mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(fmt, "{}", self.get())
+            }
+        }
+        impl ::std::cmp::PartialEq<usize> for $t {
+            #[inline]
+            fn eq(& self, int: & usize) -> bool {
+                self.get().eq(int)
+            }
+        }
+        impl ::std::cmp::PartialOrd<usize> for $t {
+            #[inline]
+            fn partial_cmp(& self, int: & usize) -> Option<
+                ::std::cmp::Ordering
+            > {
+                self.get().partial_cmp(int)
+            }
+        }
+    ) ;
+
+    // Entry point, explicit backing integer with a niche.
+    (
+        #[$cmt:meta] $t:ident repr: $int:ident with niche
+        $($tail:tt)*
+    ) => (
+        $crate::wrap_usize!{ |wrapper| #[$cmt] $t repr: $int with niche }
+        $crate::wrap_usize!{ |common| $t }
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Entry point, explicit backing integer.
+    (
+        #[$cmt:meta] $t:ident repr: $int:ident
+        $($tail:tt)*
+    ) => (
+        $crate::wrap_usize!{ |wrapper| #[$cmt] $t repr: $int }
+        $crate::wrap_usize!{ |common| $t }
+        $crate::wrap_usize!{ |internal| $t $($tail)* }
+    ) ;
+
+    // Entry point.
+    (
+        #[$cmt:meta] $t:ident
+        $($tail:tt)*
+    ) => (
+        $crate::wrap_usize!{ #[$cmt] $t repr: usize $($tail)* }
+    ) ;
+}
+
+/// Example of zero-cost wrapping. **Do not use this.**
+///
+/// This module is generated by
+///
+/// ```
+/// #[macro_use]
+/// extern crate mylib ;
+///
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Range over `VarIndex`."]
+///     range: VarRange
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+///     #[doc = "Small set of variable indices."]
+///     small set: VarSmallSet
+///     #[doc = "Dense set of variable indices."]
+///     bitset: VarBitSet
+///     #[doc = "Sparse set of variable indices."]
+///     sparse set: VarSparseSet
+///     #[doc = "Map of variable indices."]
+///     hash map: VarHMap
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+///     #[doc = "Vector indexed by variable indices, with provenance checks in debug."]
+///     tagged map: VarTaggedMap
+///     #[doc = "Interning table for values indexed by variable indices."]
+///     interner: VarTable
+///     #[doc = "Slab indexed by variable indices."]
+///     slab: VarSlab
+///     #[doc = "Slab indexed by variable indices, with generation checks."]
+///     gen slab: VarGenSlab
+///     #[doc = "Equivalence classes of variable indices."]
+///     union find: VarUf
+///     #[doc = "Graph over variable indices."]
+///     graph: VarGraph
+/// }
+/// fn main() {
+///     use std::mem::size_of ;
+///     assert_eq!( size_of::<VarIndex>(), size_of::<usize>() ) ;
+///     assert_eq!( size_of::<VarRange>(), 2 * size_of::<usize>() ) ;
+///     assert_eq!( size_of::<VarSet>(), size_of::<VarSet>() ) ;
+///     assert_eq!( size_of::<VarMap<String>>(), size_of::<Vec<String>>() ) ;
+/// }
+/// ```
+pub mod examples {
+    wrap_usize! {
+        #[doc = "Index of a variable."]
+        VarIndex
+        #[doc = "Range over `VarIndex`."]
+        range: VarRange
+        #[doc = "Set of variable indices."]
+        set: VarSet
+        #[doc = "Small set of variable indices."]
+        small set: VarSmallSet
+        #[doc = "Dense set of variable indices."]
+        bitset: VarBitSet
+        #[doc = "Sparse set of variable indices."]
+        sparse set: VarSparseSet
+        #[doc = "Map of variable indices."]
+        hash map: VarHMap
+        #[doc = "Vector indexed by variable indices."]
+        map: VarMap with iter: VarMapIter
+        #[doc = "Vector indexed by variable indices, with provenance checks in debug."]
+        tagged map: VarTaggedMap
+        #[doc = "Interning table for values indexed by variable indices."]
+        interner: VarTable
+        #[doc = "Slab indexed by variable indices."]
+        slab: VarSlab
+        #[doc = "Slab indexed by variable indices, with generation checks."]
+        gen slab: VarGenSlab
+        #[doc = "Equivalence classes of variable indices."]
+        union find: VarUf
+        #[doc = "Graph over variable indices."]
+        graph: VarGraph
+    }
+}
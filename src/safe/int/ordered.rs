@@ -0,0 +1,472 @@
+//! Hash maps and sets iterating in insertion order.
+
+use std::hash::BuildHasher;
+use std::iter::Zip;
+
+use super::{BuildHashUsize, IntHMap, IntHash};
+
+/// Iterator over the bindings of an [`IntOrderedHMap`](struct.IntOrderedHMap.html).
+pub type IntOrderedHMapIter<'a, Int, V> =
+    Zip<::std::slice::Iter<'a, Int>, ::std::slice::Iter<'a, V>>;
+/// Iterator over the bindings of an [`IntOrderedHMap`](struct.IntOrderedHMap.html), mutable
+/// version.
+pub type IntOrderedHMapIterMut<'a, Int, V> =
+    Zip<::std::slice::Iter<'a, Int>, ::std::slice::IterMut<'a, V>>;
+/// Owning iterator over the bindings of an [`IntOrderedHMap`](struct.IntOrderedHMap.html).
+pub type IntOrderedHMapIntoIter<Int, V> = Zip<::std::vec::IntoIter<Int>, ::std::vec::IntoIter<V>>;
+
+/// Hash map iterating in insertion order.
+///
+/// Keys and values are stored in vectors, in insertion order, and an
+/// [`IntHMap`](struct.IntHMap.html) maps keys to their position. Inserting a key that is already
+/// bound does not change its position. [`swap_remove`](#method.swap_remove) moves the last binding
+/// to the position of the removed one, [`shift_remove`](#method.shift_remove) (linear) preserves
+/// the order. [`sort_keys`](#method.sort_keys) reorders the bindings.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::IntOrderedHMap ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+/// }
+/// fn main() {
+///     let mut map: IntOrderedHMap<VarIndex, & str> = IntOrderedHMap::new() ;
+///     let (v_0, v_1, v_2) = ( VarIndex::new(0), VarIndex::new(1), VarIndex::new(2) ) ;
+///     map.insert(v_2, "c") ;
+///     map.insert(v_0, "a") ;
+///     map.insert(v_1, "b") ;
+///     assert_eq!( map.insert(v_2, "C"), Some("c") ) ;
+///     assert_eq!( map.keys(), & [v_2, v_0, v_1] ) ;
+///     assert_eq!( map.get_index_of(& v_0), Some(1) ) ;
+///     assert_eq!( map.shift_remove(& v_2), Some("C") ) ;
+///     assert_eq!( map.values(), & ["a", "b"] ) ;
+///     map.insert(v_2, "c") ;
+///     assert_eq!( map.swap_remove(& v_0), Some("a") ) ;
+///     assert_eq!( map.keys(), & [v_2, v_1] ) ;
+///     map.sort_keys() ;
+///     let bindings: Vec<_> = map.iter().collect() ;
+///     assert_eq!( bindings, vec![ (& v_1, & "b"), (& v_2, & "c") ] ) ;
+/// }
+/// ```
+pub struct IntOrderedHMap<Int: IntHash, V, S = BuildHashUsize> {
+    /// Keys, in order.
+    keys: Vec<Int>,
+    /// Values, in the same order as the keys.
+    vals: Vec<V>,
+    /// Position of each key.
+    index: IntHMap<Int, usize, S>,
+}
+
+impl<Int: IntHash + Clone, V: Clone, S: Clone> Clone for IntOrderedHMap<Int, V, S> {
+    fn clone(&self) -> Self {
+        IntOrderedHMap {
+            keys: self.keys.clone(),
+            vals: self.vals.clone(),
+            index: self.index.clone(),
+        }
+    }
+}
+impl<Int, V, S> ::std::fmt::Debug for IntOrderedHMap<Int, V, S>
+where
+    Int: IntHash + ::std::fmt::Debug,
+    V: ::std::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_map().entries(self.iter()).finish()
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher + Default> Default for IntOrderedHMap<Int, V, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<Int: IntHash, V: PartialEq, S: BuildHasher> PartialEq for IntOrderedHMap<Int, V, S> {
+    /// Ignores the order of the bindings.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, val)| other.get(key).map(|v| v == val).unwrap_or(false))
+    }
+}
+impl<Int: IntHash, V: Eq, S: BuildHasher> Eq for IntOrderedHMap<Int, V, S> {}
+
+impl<Int: IntHash, V, S: BuildHasher + Default> IntOrderedHMap<Int, V, S> {
+    /// Empty map.
+    #[inline]
+    pub fn new() -> Self {
+        IntOrderedHMap {
+            keys: Vec::new(),
+            vals: Vec::new(),
+            index: IntHMap::new(),
+        }
+    }
+    /// Empty map with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        IntOrderedHMap {
+            keys: Vec::with_capacity(capa),
+            vals: Vec::with_capacity(capa),
+            index: IntHMap::with_capacity(capa),
+        }
+    }
+}
+impl<Int: IntHash, V, S> IntOrderedHMap<Int, V, S> {
+    /// Number of bindings.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    /// True if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+    /// Removes all the bindings.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.vals.clear();
+        self.index.clear()
+    }
+    /// The keys, in order.
+    #[inline]
+    pub fn keys(&self) -> &[Int] {
+        &self.keys
+    }
+    /// The values, in order.
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        &self.vals
+    }
+    /// The values, in order, mutable version.
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [V] {
+        &mut self.vals
+    }
+    /// Iterates over the bindings, in order.
+    #[inline]
+    pub fn iter(&self) -> IntOrderedHMapIter<'_, Int, V> {
+        self.keys.iter().zip(self.vals.iter())
+    }
+    /// Iterates over the bindings, in order, mutable version.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IntOrderedHMapIterMut<'_, Int, V> {
+        self.keys.iter().zip(self.vals.iter_mut())
+    }
+    /// Binding at some position.
+    #[inline]
+    pub fn get_index(&self, pos: usize) -> Option<(&Int, &V)> {
+        Some((self.keys.get(pos)?, &self.vals[pos]))
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher> IntOrderedHMap<Int, V, S> {
+    /// Position of a key, if it is bound.
+    #[inline]
+    pub fn get_index_of(&self, key: &Int) -> Option<usize> {
+        self.index.get(key).cloned()
+    }
+    /// True if a key is bound.
+    #[inline]
+    pub fn contains_key(&self, key: &Int) -> bool {
+        self.index.contains_key(key)
+    }
+    /// Value of a key, if any.
+    #[inline]
+    pub fn get(&self, key: &Int) -> Option<&V> {
+        self.get_index_of(key).map(|pos| &self.vals[pos])
+    }
+    /// Value of a key, mutable version.
+    #[inline]
+    pub fn get_mut(&mut self, key: &Int) -> Option<&mut V> {
+        let pos = self.get_index_of(key)?;
+        Some(&mut self.vals[pos])
+    }
+}
+impl<Int: IntHash + Copy, V, S: BuildHasher> IntOrderedHMap<Int, V, S> {
+    /// Inserts a binding, returns the previous value of the key, if any.
+    ///
+    /// If the key is already bound, its position does not change. Otherwise the binding goes last.
+    pub fn insert(&mut self, key: Int, val: V) -> Option<V> {
+        match self.get_index_of(&key) {
+            Some(pos) => Some(::std::mem::replace(&mut self.vals[pos], val)),
+            None => {
+                self.index.insert(key, self.keys.len());
+                self.keys.push(key);
+                self.vals.push(val);
+                None
+            }
+        }
+    }
+    /// Removes a binding in constant time, returns its value if any.
+    ///
+    /// The last binding takes the position of the removed one.
+    pub fn swap_remove(&mut self, key: &Int) -> Option<V> {
+        let pos = self.index.remove(key)?;
+        self.keys.swap_remove(pos);
+        let val = self.vals.swap_remove(pos);
+        if let Some(moved) = self.keys.get(pos) {
+            self.index.insert(*moved, pos);
+        }
+        Some(val)
+    }
+    /// Removes a binding preserving the order of the others, returns its value if any.
+    ///
+    /// Linear in the number of bindings after the removed one.
+    pub fn shift_remove(&mut self, key: &Int) -> Option<V> {
+        let pos = self.index.remove(key)?;
+        self.keys.remove(pos);
+        let val = self.vals.remove(pos);
+        for (pos, moved) in self.keys.iter().enumerate().skip(pos) {
+            self.index.insert(*moved, pos);
+        }
+        Some(val)
+    }
+    /// Only keeps the bindings verifying a predicate, preserves the order.
+    pub fn retain<F: FnMut(&Int, &mut V) -> bool>(&mut self, mut keep: F) {
+        let keys = ::std::mem::take(&mut self.keys);
+        let vals = ::std::mem::take(&mut self.vals);
+        self.index.clear();
+        for (key, mut val) in keys.into_iter().zip(vals) {
+            if keep(&key, &mut val) {
+                self.insert(key, val);
+            }
+        }
+    }
+    /// Sorts the bindings with a comparison function over bindings.
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&Int, &V, &Int, &V) -> ::std::cmp::Ordering,
+    {
+        let mut bindings: Vec<_> = self.keys.drain(0..).zip(self.vals.drain(0..)).collect();
+        bindings.sort_by(|(k_1, v_1), (k_2, v_2)| cmp(k_1, v_1, k_2, v_2));
+        self.index.clear();
+        for (key, val) in bindings {
+            self.insert(key, val);
+        }
+    }
+    /// Sorts the bindings by increasing keys.
+    pub fn sort_keys(&mut self)
+    where
+        Int: Ord,
+    {
+        self.sort_by(|k_1, _, k_2, _| k_1.cmp(k_2))
+    }
+}
+impl<'a, Int: IntHash, V, S> IntoIterator for &'a IntOrderedHMap<Int, V, S> {
+    type Item = (&'a Int, &'a V);
+    type IntoIter = IntOrderedHMapIter<'a, Int, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<'a, Int: IntHash, V, S> IntoIterator for &'a mut IntOrderedHMap<Int, V, S> {
+    type Item = (&'a Int, &'a mut V);
+    type IntoIter = IntOrderedHMapIterMut<'a, Int, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+impl<Int: IntHash, V, S> IntoIterator for IntOrderedHMap<Int, V, S> {
+    type Item = (Int, V);
+    type IntoIter = IntOrderedHMapIntoIter<Int, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter().zip(self.vals)
+    }
+}
+impl<Int: IntHash + Copy, V, S: BuildHasher> Extend<(Int, V)> for IntOrderedHMap<Int, V, S> {
+    fn extend<It: IntoIterator<Item = (Int, V)>>(&mut self, iter: It) {
+        for (key, val) in iter {
+            self.insert(key, val);
+        }
+    }
+}
+impl<Int: IntHash + Copy, V, S: BuildHasher + Default> ::std::iter::FromIterator<(Int, V)>
+    for IntOrderedHMap<Int, V, S>
+{
+    fn from_iter<It: IntoIterator<Item = (Int, V)>>(iter: It) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// Hash set iterating in insertion order.
+///
+/// An [`IntOrderedHMap`](struct.IntOrderedHMap.html) with no values, same ordering rules.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::IntOrderedHSet ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+/// }
+/// fn main() {
+///     let mut set: IntOrderedHSet<VarIndex> = [5, 1, 3, 1].iter().map(
+///         |n| VarIndex::new(* n)
+///     ).collect() ;
+///     assert_eq!( set.as_slice(), & [ VarIndex::new(5), VarIndex::new(1), VarIndex::new(3) ] ) ;
+///     set.sort() ;
+///     assert_eq!( set.as_slice(), & [ VarIndex::new(1), VarIndex::new(3), VarIndex::new(5) ] ) ;
+/// }
+/// ```
+pub struct IntOrderedHSet<Int: IntHash, S = BuildHashUsize> {
+    /// Underlying map.
+    map: IntOrderedHMap<Int, (), S>,
+}
+
+impl<Int: IntHash + Clone, S: Clone> Clone for IntOrderedHSet<Int, S> {
+    fn clone(&self) -> Self {
+        IntOrderedHSet {
+            map: self.map.clone(),
+        }
+    }
+}
+impl<Int: IntHash + ::std::fmt::Debug, S> ::std::fmt::Debug for IntOrderedHSet<Int, S> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+impl<Int: IntHash, S: BuildHasher + Default> Default for IntOrderedHSet<Int, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<Int: IntHash, S: BuildHasher> PartialEq for IntOrderedHSet<Int, S> {
+    /// Ignores the order of the elements.
+    fn eq(&self, other: &Self) -> bool {
+        self.map.len() == other.map.len() && self.iter().all(|elem| other.contains(elem))
+    }
+}
+impl<Int: IntHash, S: BuildHasher> Eq for IntOrderedHSet<Int, S> {}
+
+impl<Int: IntHash, S: BuildHasher + Default> IntOrderedHSet<Int, S> {
+    /// Empty set.
+    #[inline]
+    pub fn new() -> Self {
+        IntOrderedHSet {
+            map: IntOrderedHMap::new(),
+        }
+    }
+    /// Empty set with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        IntOrderedHSet {
+            map: IntOrderedHMap::with_capacity(capa),
+        }
+    }
+}
+impl<Int: IntHash, S> IntOrderedHSet<Int, S> {
+    /// Number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+    /// True if the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+    /// Removes all the elements.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+    /// The elements, in order.
+    #[inline]
+    pub fn as_slice(&self) -> &[Int] {
+        self.map.keys()
+    }
+    /// Iterates over the elements, in order.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, Int> {
+        self.map.keys().iter()
+    }
+}
+impl<Int: IntHash, S: BuildHasher> IntOrderedHSet<Int, S> {
+    /// Position of an element, if it is in the set.
+    #[inline]
+    pub fn get_index_of(&self, elem: &Int) -> Option<usize> {
+        self.map.get_index_of(elem)
+    }
+    /// True if the set contains an element.
+    #[inline]
+    pub fn contains(&self, elem: &Int) -> bool {
+        self.map.contains_key(elem)
+    }
+}
+impl<Int: IntHash + Copy, S: BuildHasher> IntOrderedHSet<Int, S> {
+    /// Inserts an element, returns `true` if it was not there.
+    ///
+    /// If the element is already there, its position does not change. Otherwise it goes last.
+    #[inline]
+    pub fn insert(&mut self, elem: Int) -> bool {
+        self.map.insert(elem, ()).is_none()
+    }
+    /// Removes an element in constant time, returns `true` if it was there.
+    ///
+    /// The last element takes the position of the removed one.
+    #[inline]
+    pub fn swap_remove(&mut self, elem: &Int) -> bool {
+        self.map.swap_remove(elem).is_some()
+    }
+    /// Removes an element preserving the order of the others, returns `true` if it was there.
+    ///
+    /// Linear in the number of elements after the removed one.
+    #[inline]
+    pub fn shift_remove(&mut self, elem: &Int) -> bool {
+        self.map.shift_remove(elem).is_some()
+    }
+    /// Only keeps the elements verifying a predicate, preserves the order.
+    #[inline]
+    pub fn retain<F: FnMut(&Int) -> bool>(&mut self, mut keep: F) {
+        self.map.retain(|elem, _| keep(elem))
+    }
+    /// Sorts the elements with a comparison function.
+    #[inline]
+    pub fn sort_by<F: FnMut(&Int, &Int) -> ::std::cmp::Ordering>(&mut self, mut cmp: F) {
+        self.map.sort_by(|e_1, _, e_2, _| cmp(e_1, e_2))
+    }
+    /// Sorts the elements in increasing order.
+    #[inline]
+    pub fn sort(&mut self)
+    where
+        Int: Ord,
+    {
+        self.map.sort_keys()
+    }
+}
+impl<'a, Int: IntHash, S> IntoIterator for &'a IntOrderedHSet<Int, S> {
+    type Item = &'a Int;
+    type IntoIter = ::std::slice::Iter<'a, Int>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<Int: IntHash, S> IntoIterator for IntOrderedHSet<Int, S> {
+    type Item = Int;
+    type IntoIter = ::std::vec::IntoIter<Int>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.keys.into_iter()
+    }
+}
+impl<Int: IntHash + Copy, S: BuildHasher> Extend<Int> for IntOrderedHSet<Int, S> {
+    fn extend<It: IntoIterator<Item = Int>>(&mut self, iter: It) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+impl<Int: IntHash + Copy, S: BuildHasher + Default> ::std::iter::FromIterator<Int>
+    for IntOrderedHSet<Int, S>
+{
+    fn from_iter<It: IntoIterator<Item = Int>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
@@ -0,0 +1,493 @@
+//! Parallel iteration over the collections of this module, using std scoped threads.
+
+use std::hash::BuildHasher;
+use std::iter::Zip;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::{IndexVec, IntHMap, IntHSet, IntHash, IntRange, IntWrap};
+
+/// Default minimal number of items processed by each thread.
+const DEFAULT_MIN_LEN: usize = 1024;
+
+/// Lazy parallel iterator.
+///
+/// Wraps a [`ParSource`](trait.ParSource.html). [`map`](#method.map) and
+/// [`filter`](#method.filter) only stack stages on the source, nothing happens until
+/// [`for_each`](#method.for_each) or [`collect`](#method.collect). These split the source in as
+/// many chunks as there are available threads (see `std::thread::available_parallelism`), and run
+/// all the stages on each chunk in its own scoped thread. Each thread processes at least
+/// `min_len` items of the source, `1024` by default (see [`with_min_len`](#method.with_min_len)),
+/// so a source with less than `2 * min_len` items is processed sequentially in the current
+/// thread.
+///
+/// The order of the items is preserved, so that [`collect`](#method.collect)ing the result of a
+/// parallel iteration over an [`IndexVec`](struct.IndexVec.html) yields an `IndexVec` with the
+/// same indices. Collecting into an [`IntHSet`](struct.IntHSet.html) or an
+/// [`IntHMap`](struct.IntHMap.html) builds one collection per thread, and merges them.
+///
+/// Parallel iterators over `IndexVec`s split the underlying slice directly. The ones over
+/// `IntHSet`s and `IntHMap`s first collect references to the elements in a vector, sequentially,
+/// since the std hash tables cannot be split.
+///
+/// Panics in a thread are propagated to the caller.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Set of variable indices."]
+///     set: VarSet
+///     #[doc = "Map of variable indices."]
+///     hash map: VarHMap
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// fn main() {
+///     let names: VarMap<String> = (0..1000).map(|n| format!("v_{}", n)).collect() ;
+///     let lens: VarMap<usize> = names.par_iter().with_min_len(100).map(
+///         |name| name.len()
+///     ).collect() ;
+///     assert_eq!( lens[ VarIndex::new(42) ], 4 ) ;
+///
+///     let tens: VarSet = names.par_index_iter().with_min_len(100).filter(
+///         |(_, name)| name.ends_with('0')
+///     ).map( |(idx, _)| idx ).collect() ;
+///     assert_eq!( tens.len(), 100 ) ;
+///
+///     let lens: VarHMap<usize> = tens.par_iter().map( |idx| (* idx, lens[* idx]) ).collect() ;
+///     assert_eq!( lens.get( & VarIndex::new(10) ), Some(& 4) ) ;
+///
+///     let mut names = names ;
+///     names.par_iter_mut().with_min_len(10).for_each( |name| name.push('\'') ) ;
+///     assert_eq!( names[ VarIndex::new(7) ], "v_7'" ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParIter<P> {
+    /// Source of the items, with the stages to apply.
+    par: P,
+    /// Minimal number of items of the source per thread.
+    min_len: usize,
+}
+
+impl<P: ParSource> From<P> for ParIter<P> {
+    fn from(par: P) -> Self {
+        ParIter {
+            par,
+            min_len: DEFAULT_MIN_LEN,
+        }
+    }
+}
+
+impl<P: ParSource> ParIter<P> {
+    /// Sets the minimal number of items of the source processed by each thread, `1024` by
+    /// default.
+    ///
+    /// Lower it when processing an item is expensive compared to spawning a thread.
+    #[inline]
+    pub fn with_min_len(mut self, min_len: usize) -> Self {
+        self.min_len = ::std::cmp::max(min_len, 1);
+        self
+    }
+    /// Applies a function to all the items, preserves the order.
+    #[inline]
+    pub fn map<U, F>(self, f: F) -> ParIter<ParMap<P, F>>
+    where
+        F: Fn(P::Item) -> U + Send + Sync,
+    {
+        ParIter {
+            par: ParMap {
+                par: self.par,
+                f: Arc::new(f),
+            },
+            min_len: self.min_len,
+        }
+    }
+    /// Only keeps the items verifying a predicate, preserves the order.
+    #[inline]
+    pub fn filter<F>(self, keep: F) -> ParIter<ParFilter<P, F>>
+    where
+        F: Fn(&P::Item) -> bool + Send + Sync,
+    {
+        ParIter {
+            par: ParFilter {
+                par: self.par,
+                keep: Arc::new(keep),
+            },
+            min_len: self.min_len,
+        }
+    }
+    /// Applies a function to all the items in parallel.
+    pub fn for_each<F: Fn(P::Item) + Sync>(self, f: F) {
+        self.per_chunk(|chunk| chunk.for_each(&f));
+    }
+    /// Collects the items in parallel, in order.
+    #[inline]
+    pub fn collect<C: FromParIter<P::Item>>(self) -> C {
+        C::from_par_iter(self)
+    }
+
+    /// Applies a function to each chunk in its own thread, returns the results in order.
+    fn per_chunk<U: Send, F: Fn(P::Chunk) -> U + Sync>(self, f: F) -> Vec<U> {
+        let threads = ::std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let len = self.par.max_len();
+        let threads = ::std::cmp::min(threads, len / self.min_len).max(1);
+        let chunks = self.par.split(len.div_ceil(threads).max(1));
+        if chunks.len() <= 1 {
+            chunks.into_iter().map(f).collect()
+        } else {
+            let f = &f;
+            ::std::thread::scope(|scope| {
+                let handles: Vec<_> = chunks
+                    .into_iter()
+                    .map(|chunk| scope.spawn(move || f(chunk)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| ::std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            })
+        }
+    }
+}
+
+/// Items that can be split in chunks processed by different threads.
+pub trait ParSource: Sized {
+    /// Type of the items.
+    type Item;
+    /// Iterator over a chunk of the items.
+    type Chunk: Iterator<Item = Self::Item> + Send;
+    /// Number of items, before filtering.
+    fn max_len(&self) -> usize;
+    /// Splits the items in chunks of `chunk_len` items, the last one can be shorter.
+    fn split(self, chunk_len: usize) -> Vec<Self::Chunk>;
+}
+
+impl<'a, T: Sync> ParSource for &'a [T] {
+    type Item = &'a T;
+    type Chunk = ::std::slice::Iter<'a, T>;
+    fn max_len(&self) -> usize {
+        self.len()
+    }
+    fn split(self, chunk_len: usize) -> Vec<Self::Chunk> {
+        self.chunks(chunk_len).map(<[T]>::iter).collect()
+    }
+}
+impl<'a, T: Send> ParSource for &'a mut [T] {
+    type Item = &'a mut T;
+    type Chunk = ::std::slice::IterMut<'a, T>;
+    fn max_len(&self) -> usize {
+        self.len()
+    }
+    fn split(self, chunk_len: usize) -> Vec<Self::Chunk> {
+        self.chunks_mut(chunk_len).map(<[T]>::iter_mut).collect()
+    }
+}
+impl<T: Send> ParSource for Vec<T> {
+    type Item = T;
+    type Chunk = ::std::vec::IntoIter<T>;
+    fn max_len(&self) -> usize {
+        self.len()
+    }
+    /// Splits the last chunk off until there is only one left, so each item is moved once.
+    fn split(mut self, chunk_len: usize) -> Vec<Self::Chunk> {
+        let mut chunks = Vec::with_capacity(self.len().div_ceil(chunk_len));
+        while self.len() > chunk_len {
+            let last_start = (self.len() - 1) / chunk_len * chunk_len;
+            chunks.push(self.split_off(last_start).into_iter())
+        }
+        if !self.is_empty() {
+            chunks.push(self.into_iter())
+        }
+        chunks.reverse();
+        chunks
+    }
+}
+
+/// Pairs the items of a source with their wrapped index.
+///
+/// This is what [`IndexVec::par_index_iter`](struct.IndexVec.html#method.par_index_iter) and
+/// its mutable version wrap.
+#[derive(Clone, Debug)]
+pub struct ParIndexed<I, P> {
+    /// Source of the items.
+    par: P,
+    /// Phantom data for the index type.
+    _index: PhantomData<I>,
+}
+impl<I, P> ParSource for ParIndexed<I, P>
+where
+    I: IntWrap + From<usize> + Send,
+    P: ParSource,
+    P::Chunk: ExactSizeIterator,
+{
+    type Item = (I, P::Item);
+    type Chunk = Zip<IntRange<I>, P::Chunk>;
+    fn max_len(&self) -> usize {
+        self.par.max_len()
+    }
+    fn split(self, chunk_len: usize) -> Vec<Self::Chunk> {
+        let mut start = 0;
+        self.par
+            .split(chunk_len)
+            .into_iter()
+            .map(|chunk| {
+                let end = start + chunk.len();
+                let range = IntRange::new(start, end);
+                start = end;
+                range.zip(chunk)
+            })
+            .collect()
+    }
+}
+
+/// Applies a function to the items of a source, see [`ParIter::map`](struct.ParIter.html#method.map).
+///
+/// Also the iterator over a chunk of the resulting items.
+pub struct ParMap<P, F> {
+    /// Source of the items, or chunk of the source.
+    par: P,
+    /// Function to apply, shared by the chunks.
+    f: Arc<F>,
+}
+impl<U, P, F> ParSource for ParMap<P, F>
+where
+    P: ParSource,
+    F: Fn(P::Item) -> U + Send + Sync,
+{
+    type Item = U;
+    type Chunk = ParMap<P::Chunk, F>;
+    fn max_len(&self) -> usize {
+        self.par.max_len()
+    }
+    fn split(self, chunk_len: usize) -> Vec<Self::Chunk> {
+        let f = self.f;
+        self.par
+            .split(chunk_len)
+            .into_iter()
+            .map(|par| ParMap { par, f: f.clone() })
+            .collect()
+    }
+}
+impl<U, It: Iterator, F: Fn(It::Item) -> U> Iterator for ParMap<It, F> {
+    type Item = U;
+    #[inline]
+    fn next(&mut self) -> Option<U> {
+        self.par.next().map(&*self.f)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.par.size_hint()
+    }
+}
+
+/// Only keeps the items of a source verifying a predicate, see
+/// [`ParIter::filter`](struct.ParIter.html#method.filter).
+///
+/// Also the iterator over a chunk of the resulting items.
+pub struct ParFilter<P, F> {
+    /// Source of the items, or chunk of the source.
+    par: P,
+    /// Predicate, shared by the chunks.
+    keep: Arc<F>,
+}
+impl<P, F> ParSource for ParFilter<P, F>
+where
+    P: ParSource,
+    F: Fn(&P::Item) -> bool + Send + Sync,
+{
+    type Item = P::Item;
+    type Chunk = ParFilter<P::Chunk, F>;
+    fn max_len(&self) -> usize {
+        self.par.max_len()
+    }
+    fn split(self, chunk_len: usize) -> Vec<Self::Chunk> {
+        let keep = self.keep;
+        self.par
+            .split(chunk_len)
+            .into_iter()
+            .map(|par| ParFilter {
+                par,
+                keep: keep.clone(),
+            })
+            .collect()
+    }
+}
+impl<It: Iterator, F: Fn(&It::Item) -> bool> Iterator for ParFilter<It, F> {
+    type Item = It::Item;
+    #[inline]
+    fn next(&mut self) -> Option<It::Item> {
+        let keep = &*self.keep;
+        self.par.find(|item| keep(item))
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.par.size_hint().1)
+    }
+}
+
+/// Collections that can be built from a [`ParIter`](struct.ParIter.html).
+pub trait FromParIter<T>: Sized {
+    /// Builds the collection.
+    fn from_par_iter<P: ParSource<Item = T>>(par: ParIter<P>) -> Self;
+}
+impl<T: Send> FromParIter<T> for Vec<T> {
+    /// Collects each chunk in its own thread, and concatenates the results.
+    fn from_par_iter<P: ParSource<Item = T>>(par: ParIter<P>) -> Self {
+        let mut chunks = par.per_chunk(Iterator::collect::<Vec<_>>);
+        if chunks.len() == 1 {
+            return chunks.pop().unwrap_or_default();
+        }
+        let mut res = Vec::with_capacity(chunks.iter().map(Vec::len).sum());
+        for chunk in chunks {
+            res.extend(chunk)
+        }
+        res
+    }
+}
+impl<I, T: Send> FromParIter<T> for IndexVec<I, T> {
+    /// Keeps the order, so the element at position `n` gets index `n`.
+    fn from_par_iter<P: ParSource<Item = T>>(par: ParIter<P>) -> Self {
+        IndexVec::of(par.collect())
+    }
+}
+impl<Int, S> FromParIter<Int> for IntHSet<Int, S>
+where
+    Int: IntHash + Send,
+    S: BuildHasher + Default + Send,
+{
+    /// Builds a set per thread, and merges them into the biggest one.
+    fn from_par_iter<P: ParSource<Item = Int>>(par: ParIter<P>) -> Self {
+        par.per_chunk(Iterator::collect::<Self>)
+            .into_iter()
+            .reduce(|mut big, mut small| {
+                if big.len() < small.len() {
+                    ::std::mem::swap(&mut big, &mut small)
+                }
+                big.extend(small);
+                big
+            })
+            .unwrap_or_default()
+    }
+}
+impl<Int, V, S> FromParIter<(Int, V)> for IntHMap<Int, V, S>
+where
+    Int: IntHash + Send,
+    V: Send,
+    S: BuildHasher + Default + Send,
+{
+    /// Builds a map per thread, and merges them into the biggest one.
+    ///
+    /// When a key appears more than once, the binding that comes last in the items wins, as with
+    /// a sequential `collect`.
+    fn from_par_iter<P: ParSource<Item = (Int, V)>>(par: ParIter<P>) -> Self {
+        par.per_chunk(Iterator::collect::<Self>)
+            .into_iter()
+            .reduce(|mut lft, rgt| {
+                if lft.len() < rgt.len() {
+                    let mut rgt = rgt;
+                    for (key, val) in lft.drain() {
+                        rgt.entry(key).or_insert(val);
+                    }
+                    rgt
+                } else {
+                    lft.extend(rgt);
+                    lft
+                }
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl<I, T> IndexVec<I, T> {
+    /// Parallel iterator over the elements.
+    #[inline]
+    pub fn par_iter(&self) -> ParIter<&[T]>
+    where
+        T: Sync,
+    {
+        self.as_slice().raw().into()
+    }
+    /// Parallel iterator over the elements, mutable version.
+    #[inline]
+    pub fn par_iter_mut(&mut self) -> ParIter<&mut [T]>
+    where
+        T: Send,
+    {
+        self.as_mut_slice().raw_mut().into()
+    }
+    /// Parallel iterator over the elements, consumes the vector.
+    #[inline]
+    pub fn into_par_iter(self) -> ParIter<Vec<T>>
+    where
+        T: Send,
+    {
+        self.into_vec().into()
+    }
+}
+impl<I: IntWrap + From<usize> + Send, T> IndexVec<I, T> {
+    /// Parallel iterator over the elements and their index.
+    #[inline]
+    pub fn par_index_iter(&self) -> ParIter<ParIndexed<I, &[T]>>
+    where
+        T: Sync,
+    {
+        ParIndexed {
+            par: self.as_slice().raw(),
+            _index: PhantomData,
+        }
+        .into()
+    }
+    /// Parallel iterator over the elements and their index, mutable version.
+    #[inline]
+    pub fn par_index_iter_mut(&mut self) -> ParIter<ParIndexed<I, &mut [T]>>
+    where
+        T: Send,
+    {
+        ParIndexed {
+            par: self.as_mut_slice().raw_mut(),
+            _index: PhantomData,
+        }
+        .into()
+    }
+}
+impl<Int: IntHash + Sync, S> IntHSet<Int, S> {
+    /// Parallel iterator over the elements.
+    ///
+    /// Collects references to the elements in a vector first, sequentially.
+    #[inline]
+    pub fn par_iter(&self) -> ParIter<Vec<&Int>> {
+        self.iter().collect::<Vec<_>>().into()
+    }
+}
+impl<Int: IntHash + Sync, V, S> IntHMap<Int, V, S> {
+    /// Parallel iterator over the bindings.
+    ///
+    /// Collects references to the bindings in a vector first, sequentially.
+    #[inline]
+    pub fn par_iter(&self) -> ParIter<Vec<(&Int, &V)>>
+    where
+        V: Sync,
+    {
+        self.iter().collect::<Vec<_>>().into()
+    }
+    /// Parallel iterator over the bindings, mutable version.
+    ///
+    /// Collects references to the bindings in a vector first, sequentially.
+    #[inline]
+    pub fn par_iter_mut(&mut self) -> ParIter<Vec<(&Int, &mut V)>>
+    where
+        V: Send,
+    {
+        self.iter_mut().collect::<Vec<_>>().into()
+    }
+}
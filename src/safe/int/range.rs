@@ -0,0 +1,114 @@
+//! Ranges of wrapped indices.
+
+use super::IntWrap;
+
+/// Range over wrapped indices, from `start` (inclusive) to `end` (exclusive).
+///
+/// This is what the `range` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as a
+/// type alias. Iterating over a range yields the indices it contains, in increasing order.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Range over `VarIndex`."]
+///     range: VarRange
+/// }
+/// fn main() {
+///     let range = VarRange::new(3, 6) ;
+///     assert_eq!( range.len(), 3 ) ;
+///     let indices: Vec<VarIndex> = range.rev().collect() ;
+///     assert_eq!( indices, vec![ VarIndex::new(5), VarIndex::new(4), VarIndex::new(3) ] ) ;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntRange<I> {
+    /// First index of the range.
+    start: I,
+    /// First index after the range.
+    end: I,
+}
+impl<I> IntRange<I> {
+    /// Creates a new range.
+    pub fn new<T1: Into<I>, T2: Into<I>>(start: T1, end: T2) -> Self {
+        IntRange {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+    /// Start of the range (inclusive).
+    #[inline]
+    pub fn start(&self) -> &I {
+        &self.start
+    }
+    /// End of the range (exclusive).
+    #[inline]
+    pub fn end(&self) -> &I {
+        &self.end
+    }
+}
+impl<I: From<usize>> IntRange<I> {
+    /// Creates a range from `0` to something.
+    pub fn zero_to<T: Into<I>>(end: T) -> Self {
+        IntRange {
+            start: 0.into(),
+            end: end.into(),
+        }
+    }
+}
+impl<I: IntWrap> IntRange<I> {
+    /// True if the range contains some index.
+    #[inline]
+    pub fn contains(&self, index: &I) -> bool {
+        self.start.inner() <= index.inner() && index.inner() < self.end.inner()
+    }
+    /// The range as a range of `usize`s.
+    #[inline]
+    pub fn to_usize(&self) -> ::std::ops::Range<usize> {
+        self.start.inner()..self.end.inner()
+    }
+}
+impl<I: IntWrap + From<usize>> Iterator for IntRange<I> {
+    type Item = I;
+    fn next(&mut self) -> Option<I> {
+        if self.start.inner() >= self.end.inner() {
+            None
+        } else {
+            let next = I::from(self.start.inner() + 1);
+            Some(::std::mem::replace(&mut self.start, next))
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end.inner().saturating_sub(self.start.inner());
+        (len, Some(len))
+    }
+}
+impl<I: IntWrap + From<usize>> DoubleEndedIterator for IntRange<I> {
+    fn next_back(&mut self) -> Option<I> {
+        if self.start.inner() >= self.end.inner() {
+            None
+        } else {
+            self.end = I::from(self.end.inner() - 1);
+            Some(I::from(self.end.inner()))
+        }
+    }
+}
+impl<I: IntWrap + From<usize>> ExactSizeIterator for IntRange<I> {}
+impl<I> ::std::ops::RangeBounds<I> for IntRange<I> {
+    fn start_bound(&self) -> ::std::ops::Bound<&I> {
+        ::std::ops::Bound::Included(&self.start)
+    }
+    fn end_bound(&self) -> ::std::ops::Bound<&I> {
+        ::std::ops::Bound::Excluded(&self.end)
+    }
+}
+impl<I> From<::std::ops::Range<I>> for IntRange<I> {
+    fn from(range: ::std::ops::Range<I>) -> Self {
+        IntRange {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
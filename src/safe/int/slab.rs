@@ -0,0 +1,391 @@
+//! Slab indexed by wrapped indices, recycling the indices of removed elements.
+
+use std::marker::PhantomData;
+
+use super::IntWrap;
+
+/// Key of an [`IntSlab`](struct.IntSlab.html).
+///
+/// Implemented by all wrapped indices (no generation check) and by
+/// [`GenIndex`](struct.GenIndex.html) (generation check).
+pub trait SlabKey: Copy {
+    /// Index of the slot of the key.
+    fn slot(&self) -> usize;
+    /// Generation of the key, `None` if the key does not track generations.
+    fn generation(&self) -> Option<u32>;
+    /// Creates a key from a slot index and a generation.
+    fn of_slot(slot: usize, generation: u32) -> Self;
+}
+impl<I: IntWrap + From<usize> + Copy> SlabKey for I {
+    #[inline]
+    fn slot(&self) -> usize {
+        self.inner()
+    }
+    #[inline]
+    fn generation(&self) -> Option<u32> {
+        None
+    }
+    #[inline]
+    fn of_slot(slot: usize, _: u32) -> Self {
+        slot.into()
+    }
+}
+
+/// Wrapped index with a generation, used to detect stale keys in an
+/// [`IntSlab`](struct.IntSlab.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GenIndex<I> {
+    /// Index of the slot.
+    index: I,
+    /// Generation of the slot when the key was created.
+    generation: u32,
+}
+impl<I: Copy> GenIndex<I> {
+    /// Index of the slot.
+    #[inline]
+    pub fn index(&self) -> I {
+        self.index
+    }
+    /// Generation of the slot when the key was created.
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+impl<I: IntWrap + From<usize> + Copy> SlabKey for GenIndex<I> {
+    #[inline]
+    fn slot(&self) -> usize {
+        self.index.inner()
+    }
+    #[inline]
+    fn generation(&self) -> Option<u32> {
+        Some(self.generation)
+    }
+    #[inline]
+    fn of_slot(slot: usize, generation: u32) -> Self {
+        GenIndex {
+            index: slot.into(),
+            generation,
+        }
+    }
+}
+
+/// Content of a slot.
+#[derive(Clone, Debug)]
+enum Entry<T> {
+    /// Slot storing an element.
+    Occupied(T),
+    /// Free slot, with the next free slot.
+    Vacant(Option<usize>),
+}
+
+/// Slot of a slab.
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    /// Incremented each time the slot is freed.
+    generation: u32,
+    /// Content.
+    entry: Entry<T>,
+}
+
+/// Vector indexed by wrapped indices, where removing an element frees its slot for later
+/// insertions.
+///
+/// This is what the `slab` and `gen slab` tags of [`wrap_usize`](../../macro.wrap_usize.html)
+/// expand to, as type aliases. The former uses the wrapped indices as keys. The latter uses
+/// [`GenIndex`](struct.GenIndex.html)es, which remember the generation of their slot: using a
+/// key after its element was removed is detected even if the slot was reused since. `get` returns
+/// `None` in this case, while indexing panics in debug.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a term."]
+///     TermIndex
+///     #[doc = "Slab of terms."]
+///     slab: TermSlab
+///     #[doc = "Slab of terms with generation checks."]
+///     gen slab: TermGenSlab
+/// }
+/// fn main() {
+///     let mut terms: TermSlab<& str> = TermSlab::new() ;
+///     let x = terms.insert("x") ;
+///     let y = terms.insert("y") ;
+///     assert_eq!( terms.remove(x), Some("x") ) ;
+///     assert_eq!( terms.get(x), None ) ;
+///     // Slot of `x` is reused.
+///     let z = terms.insert("z") ;
+///     assert_eq!( z, x ) ;
+///     assert_eq!( terms[y], "y" ) ;
+///     assert_eq!( terms.len(), 2 ) ;
+///
+///     let mut terms: TermGenSlab<& str> = TermGenSlab::new() ;
+///     let x = terms.insert("x") ;
+///     terms.remove(x) ;
+///     let z = terms.insert("z") ;
+///     assert_eq!( z.index(), x.index() ) ;
+///     // `x` is stale.
+///     assert_eq!( terms.get(x), None ) ;
+///     assert_eq!( terms.get(z), Some(& "z") ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct IntSlab<K, T> {
+    /// Slots.
+    slots: Vec<Slot<T>>,
+    /// First free slot.
+    free: Option<usize>,
+    /// Number of elements.
+    len: usize,
+    /// Phantom data for the key type.
+    _key: PhantomData<K>,
+}
+
+impl<K, T> Default for IntSlab<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T> IntSlab<K, T> {
+    /// Empty slab.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+    /// Empty slab with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        IntSlab {
+            slots: Vec::with_capacity(capa),
+            free: None,
+            len: 0,
+            _key: PhantomData,
+        }
+    }
+    /// Number of elements in the slab.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// True if the slab is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Number of slots, free or not.
+    #[inline]
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+    /// Capacity of the slab.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Frees a slot, assumed occupied.
+    fn free_slot(&mut self, slot: usize) -> T {
+        let next = self.free;
+        let slot_ref = &mut self.slots[slot];
+        slot_ref.generation = slot_ref.generation.wrapping_add(1);
+        self.free = Some(slot);
+        self.len -= 1;
+        match ::std::mem::replace(&mut slot_ref.entry, Entry::Vacant(next)) {
+            Entry::Occupied(elem) => elem,
+            Entry::Vacant(_) => panic!("[bug] freeing a free slab slot"),
+        }
+    }
+}
+
+impl<K: SlabKey, T> IntSlab<K, T> {
+    /// Slot of a key if it is occupied, and the key is not stale.
+    #[inline]
+    fn live_slot(&self, key: K) -> Option<&Slot<T>> {
+        self.slots.get(key.slot()).filter(|slot| {
+            matches!(slot.entry, Entry::Occupied(_))
+                && key.generation().is_none_or(|gen| gen == slot.generation)
+        })
+    }
+
+    /// The key the next insertion will return.
+    pub fn next_key(&self) -> K {
+        match self.free {
+            Some(slot) => K::of_slot(slot, self.slots[slot].generation),
+            None => K::of_slot(self.slots.len(), 0),
+        }
+    }
+    /// Inserts an element, returns its key.
+    #[inline]
+    pub fn insert(&mut self, elem: T) -> K {
+        self.insert_with(|_| elem)
+    }
+    /// Inserts the element produced by `f` applied to the key of this element, returns the key.
+    pub fn insert_with<F: FnOnce(K) -> T>(&mut self, f: F) -> K {
+        let key = self.next_key();
+        let elem = f(key);
+        match self.free {
+            Some(slot) => {
+                let entry = ::std::mem::replace(&mut self.slots[slot].entry, Entry::Occupied(elem));
+                if let Entry::Vacant(next) = entry {
+                    self.free = next
+                } else {
+                    panic!("[bug] slab free list points to an occupied slot")
+                }
+            }
+            None => self.slots.push(Slot {
+                generation: 0,
+                entry: Entry::Occupied(elem),
+            }),
+        }
+        self.len += 1;
+        key
+    }
+    /// True if the slab has an element for some key.
+    #[inline]
+    pub fn contains(&self, key: K) -> bool {
+        self.live_slot(key).is_some()
+    }
+    /// Reference to the element of a key, `None` if there is none or the key is stale.
+    #[inline]
+    pub fn get(&self, key: K) -> Option<&T> {
+        match self.live_slot(key) {
+            Some(Slot {
+                entry: Entry::Occupied(elem),
+                ..
+            }) => Some(elem),
+            _ => None,
+        }
+    }
+    /// Mutable reference to the element of a key, `None` if there is none or the key is stale.
+    #[inline]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut T> {
+        self.live_slot(key)?;
+        match self.slots[key.slot()].entry {
+            Entry::Occupied(ref mut elem) => Some(elem),
+            Entry::Vacant(_) => None,
+        }
+    }
+    /// Removes the element of a key, `None` if there is none or the key is stale.
+    ///
+    /// The slot of the element will be reused by later insertions.
+    pub fn remove(&mut self, key: K) -> Option<T> {
+        self.live_slot(key)?;
+        Some(self.free_slot(key.slot()))
+    }
+    /// Removes all the elements verifying a predicate.
+    pub fn retain<F: FnMut(K, &mut T) -> bool>(&mut self, mut pred: F) {
+        for slot in 0..self.slots.len() {
+            let generation = self.slots[slot].generation;
+            let keep = match self.slots[slot].entry {
+                Entry::Occupied(ref mut elem) => pred(K::of_slot(slot, generation), elem),
+                Entry::Vacant(_) => true,
+            };
+            if !keep {
+                self.free_slot(slot);
+            }
+        }
+    }
+    /// Removes all the elements, keys of the removed elements are stale.
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false)
+    }
+    /// Iterates over the elements and their key.
+    #[inline]
+    pub fn iter(&self) -> IntSlabIter<'_, K, T> {
+        IntSlabIter {
+            slots: self.slots.iter().enumerate(),
+            _key: PhantomData,
+        }
+    }
+    /// Iterates over the elements and their key, mutable version.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IntSlabIterMut<'_, K, T> {
+        IntSlabIterMut {
+            slots: self.slots.iter_mut().enumerate(),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K: SlabKey, T> ::std::ops::Index<K> for IntSlab<K, T> {
+    type Output = T;
+    fn index(&self, key: K) -> &T {
+        let slot = &self.slots[key.slot()];
+        debug_assert! {
+            key.generation().is_none_or(|gen| gen == slot.generation),
+            "[illegal] stale key used to access a slab"
+        }
+        match slot.entry {
+            Entry::Occupied(ref elem) => elem,
+            Entry::Vacant(_) => panic!("[illegal] key of a removed element used to access a slab"),
+        }
+    }
+}
+impl<K: SlabKey, T> ::std::ops::IndexMut<K> for IntSlab<K, T> {
+    fn index_mut(&mut self, key: K) -> &mut T {
+        let slot = &mut self.slots[key.slot()];
+        debug_assert! {
+            key.generation().is_none_or(|gen| gen == slot.generation),
+            "[illegal] stale key used to access a slab"
+        }
+        match slot.entry {
+            Entry::Occupied(ref mut elem) => elem,
+            Entry::Vacant(_) => panic!("[illegal] key of a removed element used to access a slab"),
+        }
+    }
+}
+impl<'a, K: SlabKey, T> IntoIterator for &'a IntSlab<K, T> {
+    type Item = (K, &'a T);
+    type IntoIter = IntSlabIter<'a, K, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<'a, K: SlabKey, T> IntoIterator for &'a mut IntSlab<K, T> {
+    type Item = (K, &'a mut T);
+    type IntoIter = IntSlabIterMut<'a, K, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over the elements of an [`IntSlab`](struct.IntSlab.html) and their key.
+pub struct IntSlabIter<'a, K, T> {
+    /// Underlying iterator.
+    slots: ::std::iter::Enumerate<::std::slice::Iter<'a, Slot<T>>>,
+    /// Phantom data for the key type.
+    _key: PhantomData<K>,
+}
+impl<'a, K: SlabKey, T> Iterator for IntSlabIter<'a, K, T> {
+    type Item = (K, &'a T);
+    fn next(&mut self) -> Option<(K, &'a T)> {
+        for (index, slot) in &mut self.slots {
+            if let Entry::Occupied(ref elem) = slot.entry {
+                return Some((K::of_slot(index, slot.generation), elem));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the elements of an [`IntSlab`](struct.IntSlab.html) and their key, mutable
+/// version.
+pub struct IntSlabIterMut<'a, K, T> {
+    /// Underlying iterator.
+    slots: ::std::iter::Enumerate<::std::slice::IterMut<'a, Slot<T>>>,
+    /// Phantom data for the key type.
+    _key: PhantomData<K>,
+}
+impl<'a, K: SlabKey, T> Iterator for IntSlabIterMut<'a, K, T> {
+    type Item = (K, &'a mut T);
+    fn next(&mut self) -> Option<(K, &'a mut T)> {
+        for (index, slot) in &mut self.slots {
+            if let Entry::Occupied(ref mut elem) = slot.entry {
+                return Some((K::of_slot(index, slot.generation), elem));
+            }
+        }
+        None
+    }
+}
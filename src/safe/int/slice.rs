@@ -0,0 +1,349 @@
+//! Slices indexed by wrapped indices.
+
+use std::marker::PhantomData;
+use std::ops::{
+    Index, IndexMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+
+use super::{IndexIter, IndexVec, IntRange, IntWrap};
+
+/// Slice forcing to use `I` instead of `usize` to access elements.
+///
+/// Obtained by indexing an [`IndexVec`](struct.IndexVec.html) (or another slice) with a range of
+/// `I`s: an [`IntRange`](struct.IntRange.html) or a `std` range. Indices are relative to the
+/// start of the slice, like for normal slices.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::IndexSlice ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Range over `VarIndex`."]
+///     range: VarRange
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// /// Index of the maximum of a slice.
+/// fn max_index(slice: & IndexSlice<VarIndex, usize>) -> Option<VarIndex> {
+///     slice.index_iter().max_by_key(|(_, val)| ** val).map(|(idx, _)| idx)
+/// }
+/// fn main() {
+///     let map: VarMap<usize> = vec![ 1, 3, 5, 7, 2, 4 ].into() ;
+///     let slice = & map[ VarRange::new(1, 5) ] ;
+///     assert_eq!( slice.len(), 4 ) ;
+///     assert_eq!( slice[ VarIndex::zero() ], 3 ) ;
+///     assert_eq!( max_index(slice), Some( VarIndex::new(2) ) ) ;
+///     assert_eq!( slice.binary_search(& 5), Ok( VarIndex::one() ) ) ;
+///
+///     let (left, right) = map.split_at( VarIndex::new(2) ) ;
+///     assert_eq!( left.raw(), & [1, 3] ) ;
+///     assert_eq!( right[ VarIndex::zero() .. VarIndex::one() ].raw(), & [5] ) ;
+///     assert_eq!( map.chunks(4).map(|chunk| chunk.len()).collect::<Vec<_>>(), vec![4, 2] ) ;
+/// }
+/// ```
+#[repr(transparent)]
+pub struct IndexSlice<I, T> {
+    /// Phantom data for the index type.
+    _index: PhantomData<I>,
+    /// Elements.
+    raw: [T],
+}
+
+impl<I, T> IndexSlice<I, T> {
+    /// Wraps a slice.
+    #[inline]
+    pub fn from_raw(raw: &[T]) -> &Self {
+        // Safe because `IndexSlice` is a transparent wrapper around `[T]`.
+        unsafe { &*(raw as *const [T] as *const Self) }
+    }
+    /// Wraps a mutable slice.
+    #[inline]
+    pub fn from_raw_mut(raw: &mut [T]) -> &mut Self {
+        // Safe because `IndexSlice` is a transparent wrapper around `[T]`.
+        unsafe { &mut *(raw as *mut [T] as *mut Self) }
+    }
+    /// Underlying slice.
+    #[inline]
+    pub fn raw(&self) -> &[T] {
+        &self.raw
+    }
+    /// Underlying slice, mutable version.
+    #[inline]
+    pub fn raw_mut(&mut self) -> &mut [T] {
+        &mut self.raw
+    }
+    /// Number of elements in the slice.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.raw.len()
+    }
+    /// True if the slice is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+    }
+    /// Iterates over the elements.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, T> {
+        self.raw.iter()
+    }
+    /// Iterates over the elements (mutable version).
+    #[inline]
+    pub fn iter_mut(&mut self) -> ::std::slice::IterMut<'_, T> {
+        self.raw.iter_mut()
+    }
+    /// Iterates over chunks of `size` elements, the last one can be shorter.
+    ///
+    /// Panics if `size` is `0`.
+    #[inline]
+    pub fn chunks(&self, size: usize) -> IndexChunks<'_, I, T> {
+        IndexChunks {
+            chunks: self.raw.chunks(size),
+            _index: PhantomData,
+        }
+    }
+    /// Iterates over chunks of `size` elements, the last one can be shorter (mutable version).
+    ///
+    /// Panics if `size` is `0`.
+    #[inline]
+    pub fn chunks_mut(&mut self, size: usize) -> IndexChunksMut<'_, I, T> {
+        IndexChunksMut {
+            chunks: self.raw.chunks_mut(size),
+            _index: PhantomData,
+        }
+    }
+}
+
+impl<I: IntWrap + From<usize>, T> IndexSlice<I, T> {
+    /// Iterates over the elements with the index.
+    #[inline]
+    pub fn index_iter(&self) -> IndexIter<I, ::std::slice::Iter<'_, T>> {
+        IndexIter::new(self.raw.iter())
+    }
+    /// Iterates over the elements with the index, mutable version.
+    #[inline]
+    pub fn index_iter_mut(&mut self) -> IndexIter<I, ::std::slice::IterMut<'_, T>> {
+        IndexIter::new(self.raw.iter_mut())
+    }
+    /// Reference to an element, `None` if the index is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: I) -> Option<&T> {
+        self.raw.get(idx.inner())
+    }
+    /// Mutable reference to an element, `None` if the index is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, idx: I) -> Option<&mut T> {
+        self.raw.get_mut(idx.inner())
+    }
+    /// The index of the last element, `None` if the slice is empty.
+    #[inline]
+    pub fn last_index(&self) -> Option<I> {
+        self.len().checked_sub(1).map(I::from)
+    }
+    /// Range of the indices of the slice.
+    #[inline]
+    pub fn indices(&self) -> IntRange<I> {
+        IntRange::zero_to(self.len())
+    }
+    /// Splits the slice in two at an index.
+    ///
+    /// The first slice contains the indices strictly lower than `mid`. Panics if `mid` is greater
+    /// than the length of the slice.
+    #[inline]
+    pub fn split_at(&self, mid: I) -> (&Self, &Self) {
+        let (left, right) = self.raw.split_at(mid.inner());
+        (Self::from_raw(left), Self::from_raw(right))
+    }
+    /// Splits the slice in two at an index (mutable version).
+    ///
+    /// The first slice contains the indices strictly lower than `mid`. Panics if `mid` is greater
+    /// than the length of the slice.
+    #[inline]
+    pub fn split_at_mut(&mut self, mid: I) -> (&mut Self, &mut Self) {
+        let (left, right) = self.raw.split_at_mut(mid.inner());
+        (Self::from_raw_mut(left), Self::from_raw_mut(right))
+    }
+    /// Binary search in a sorted slice, see `slice::binary_search`.
+    #[inline]
+    pub fn binary_search(&self, elem: &T) -> Result<I, I>
+    where
+        T: Ord,
+    {
+        self.raw.binary_search(elem).map(I::from).map_err(I::from)
+    }
+    /// Binary search in a sorted slice, see `slice::binary_search_by`.
+    #[inline]
+    pub fn binary_search_by<F>(&self, f: F) -> Result<I, I>
+    where
+        F: FnMut(&T) -> ::std::cmp::Ordering,
+    {
+        self.raw.binary_search_by(f).map(I::from).map_err(I::from)
+    }
+    /// Binary search in a sorted slice, see `slice::binary_search_by_key`.
+    #[inline]
+    pub fn binary_search_by_key<B: Ord, F>(&self, key: &B, f: F) -> Result<I, I>
+    where
+        F: FnMut(&T) -> B,
+    {
+        self.raw
+            .binary_search_by_key(key, f)
+            .map(I::from)
+            .map_err(I::from)
+    }
+}
+
+impl<I, T: Clone> IndexSlice<I, T> {
+    /// Copies the slice into a new map.
+    #[inline]
+    pub fn to_index_vec(&self) -> IndexVec<I, T> {
+        IndexVec::of(self.raw.to_vec())
+    }
+}
+
+impl<I, T: ::std::fmt::Debug> ::std::fmt::Debug for IndexSlice<I, T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.raw.fmt(fmt)
+    }
+}
+impl<I, T: PartialEq> PartialEq for IndexSlice<I, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw.eq(&other.raw)
+    }
+}
+impl<I, T: Eq> Eq for IndexSlice<I, T> {}
+
+impl<'a, I, T> IntoIterator for &'a IndexSlice<I, T> {
+    type Item = &'a T;
+    type IntoIter = ::std::slice::Iter<'a, T>;
+    fn into_iter(self) -> ::std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+impl<'a, I, T> IntoIterator for &'a mut IndexSlice<I, T> {
+    type Item = &'a mut T;
+    type IntoIter = ::std::slice::IterMut<'a, T>;
+    fn into_iter(self) -> ::std::slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<I: IntWrap, T> Index<I> for IndexSlice<I, T> {
+    type Output = T;
+    fn index(&self, index: I) -> &T {
+        &self.raw[index.inner()]
+    }
+}
+impl<I: IntWrap, T> IndexMut<I> for IndexSlice<I, T> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.raw[index.inner()]
+    }
+}
+
+/// Implements slicing by a range of wrapped indices for `IndexSlice` and `IndexVec`.
+macro_rules! impl_range_index {
+    ($($range:ty => |$r:ident| $raw:expr),* $(,)*) => {$(
+        impl<I: IntWrap, T> Index<$range> for IndexSlice<I, T> {
+            type Output = IndexSlice<I, T>;
+            #[inline]
+            fn index(&self, $r: $range) -> &IndexSlice<I, T> {
+                IndexSlice::from_raw(&self.raw[$raw])
+            }
+        }
+        impl<I: IntWrap, T> IndexMut<$range> for IndexSlice<I, T> {
+            #[inline]
+            fn index_mut(&mut self, $r: $range) -> &mut IndexSlice<I, T> {
+                IndexSlice::from_raw_mut(&mut self.raw[$raw])
+            }
+        }
+        impl<I: IntWrap, T> Index<$range> for IndexVec<I, T> {
+            type Output = IndexSlice<I, T>;
+            #[inline]
+            fn index(&self, range: $range) -> &IndexSlice<I, T> {
+                &self.as_slice()[range]
+            }
+        }
+        impl<I: IntWrap, T> IndexMut<$range> for IndexVec<I, T> {
+            #[inline]
+            fn index_mut(&mut self, range: $range) -> &mut IndexSlice<I, T> {
+                &mut self.as_mut_slice()[range]
+            }
+        }
+    )*};
+}
+impl_range_index! {
+    IntRange<I> => |r| r.start().inner()..r.end().inner(),
+    Range<I> => |r| r.start.inner()..r.end.inner(),
+    RangeFrom<I> => |r| r.start.inner()..,
+    RangeTo<I> => |r| ..r.end.inner(),
+    RangeInclusive<I> => |r| r.start().inner()..=r.end().inner(),
+    RangeToInclusive<I> => |r| ..=r.end.inner(),
+}
+impl<I, T> Index<RangeFull> for IndexVec<I, T> {
+    type Output = IndexSlice<I, T>;
+    #[inline]
+    fn index(&self, _: RangeFull) -> &IndexSlice<I, T> {
+        self.as_slice()
+    }
+}
+impl<I, T> IndexMut<RangeFull> for IndexVec<I, T> {
+    #[inline]
+    fn index_mut(&mut self, _: RangeFull) -> &mut IndexSlice<I, T> {
+        self.as_mut_slice()
+    }
+}
+
+/// Iterator over chunks of an [`IndexSlice`](struct.IndexSlice.html).
+#[derive(Debug, Clone)]
+pub struct IndexChunks<'a, I, T> {
+    /// Underlying iterator.
+    chunks: ::std::slice::Chunks<'a, T>,
+    /// Phantom data for the index type.
+    _index: PhantomData<I>,
+}
+impl<'a, I: 'a, T> Iterator for IndexChunks<'a, I, T> {
+    type Item = &'a IndexSlice<I, T>;
+    #[inline]
+    fn next(&mut self) -> Option<&'a IndexSlice<I, T>> {
+        self.chunks.next().map(IndexSlice::from_raw)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+impl<'a, I: 'a, T> DoubleEndedIterator for IndexChunks<'a, I, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a IndexSlice<I, T>> {
+        self.chunks.next_back().map(IndexSlice::from_raw)
+    }
+}
+impl<'a, I: 'a, T> ExactSizeIterator for IndexChunks<'a, I, T> {}
+
+/// Iterator over chunks of an [`IndexSlice`](struct.IndexSlice.html), mutable version.
+#[derive(Debug)]
+pub struct IndexChunksMut<'a, I, T> {
+    /// Underlying iterator.
+    chunks: ::std::slice::ChunksMut<'a, T>,
+    /// Phantom data for the index type.
+    _index: PhantomData<I>,
+}
+impl<'a, I: 'a, T> Iterator for IndexChunksMut<'a, I, T> {
+    type Item = &'a mut IndexSlice<I, T>;
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut IndexSlice<I, T>> {
+        self.chunks.next().map(IndexSlice::from_raw_mut)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+impl<'a, I: 'a, T> DoubleEndedIterator for IndexChunksMut<'a, I, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut IndexSlice<I, T>> {
+        self.chunks.next_back().map(IndexSlice::from_raw_mut)
+    }
+}
+impl<'a, I: 'a, T> ExactSizeIterator for IndexChunksMut<'a, I, T> {}
@@ -0,0 +1,742 @@
+//! Sets of wrapped indices stored inline while they are small.
+
+use std::hash::{BuildHasher, Hash};
+use std::iter::Take;
+
+use super::{unordered_hash, BuildHashUsize, IntHSet, IntHSetSortedIter, IntHash};
+use crate::common::hash::HashSet;
+
+/// Representation of a small set.
+enum Repr<Int: IntHash, const N: usize, S> {
+    /// Sorted elements, only the first `len` are in the set.
+    Inline {
+        /// Number of elements.
+        len: usize,
+        /// Elements, created by the first insertion and filled with copies of the first element.
+        /// The positions after `len` hold stale copies of elements.
+        elems: Option<[Int; N]>,
+        /// Hasher, for when the set spills.
+        hasher: S,
+    },
+    /// Hash set, once the set outgrew the inline array.
+    Spilled(IntHSet<Int, S>),
+}
+
+/// Hash set of wrapped indices, storing up to `N` elements inline.
+///
+/// This is what the `small set` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as
+/// a type alias, with `N = 8`. Elements are stored in a sorted array inside the set until there
+/// are more than `N` of them, at which point they move to an [`IntHSet`](struct.IntHSet.html).
+/// The set does not move back to the inline array afterwards, even when cleared.
+///
+/// Offers the same API as `IntHSet`, including set operations, hashing and ordering, so that it
+/// can replace an `IntHSet` directly. Iteration follows increasing order while the set is inline,
+/// no particular order afterwards.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Small set of variable indices."]
+///     small set: VarSmallSet
+/// }
+/// fn main() {
+///     let mut fvars = VarSmallSet::new() ;
+///     assert!( fvars.insert( VarIndex::new(3) ) ) ;
+///     assert!( fvars.insert( VarIndex::new(1) ) ) ;
+///     assert!( ! fvars.insert( VarIndex::new(3) ) ) ;
+///     assert!( ! fvars.is_spilled() ) ;
+///     let elems: Vec<_> = fvars.iter().map(|v| v.get()).collect() ;
+///     assert_eq!( elems, vec![1, 3] ) ;
+///
+///     fvars.extend( (10 .. 20).map(VarIndex::new) ) ;
+///     assert!( fvars.is_spilled() ) ;
+///     assert_eq!( fvars.len(), 12 ) ;
+///     assert!( fvars.remove( & VarIndex::new(1) ) ) ;
+///     assert!( ! fvars.contains( & VarIndex::new(1) ) ) ;
+///     assert!( fvars.contains( & VarIndex::new(15) ) ) ;
+/// }
+/// ```
+///
+/// Set operations, hashing and ordering do not depend on the representation.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use std::collections::HashSet ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Small set of variable indices."]
+///     small set: VarSmallSet
+/// }
+/// fn main() {
+///     let lft: VarSmallSet = (0..4).map(VarIndex::new).collect() ;
+///     let rgt: VarSmallSet = (2..20).map(VarIndex::new).collect() ;
+///     assert!( ! lft.is_spilled() && rgt.is_spilled() ) ;
+///     let expected: VarSmallSet = (2..4).map(VarIndex::new).collect() ;
+///     assert_eq!( & lft & & rgt, expected ) ;
+///     assert!( ( & lft | & rgt ).is_superset(& rgt) ) ;
+///     let diff = ( & lft - & rgt ).into_sorted_vec() ;
+///     assert_eq!( diff, vec![ VarIndex::new(0), VarIndex::new(1) ] ) ;
+///     assert_eq!( ( & lft ^ & rgt ).len(), 18 ) ;
+///
+///     let mut spilled: VarSmallSet = (0..20).map(VarIndex::new).collect() ;
+///     spilled.retain( |v| v.get() >= 2 && v.get() < 4 ) ;
+///     assert!( spilled.is_spilled() ) ;
+///     assert_eq!( spilled, expected ) ;
+///     assert!( spilled <= expected && spilled >= expected ) ;
+///     let mut hashed = HashSet::new() ;
+///     hashed.insert(spilled) ;
+///     assert!( hashed.contains(& expected) ) ;
+///
+///     let mut drained = rgt.clone() ;
+///     assert_eq!( drained.take( & VarIndex::new(7) ), Some( VarIndex::new(7) ) ) ;
+///     assert_eq!( drained.drain().count(), 17 ) ;
+///     assert!( drained.is_empty() ) ;
+/// }
+/// ```
+pub struct IntSmallSet<Int: IntHash, const N: usize = 8, S = BuildHashUsize> {
+    /// Actual set.
+    repr: Repr<Int, N, S>,
+}
+
+impl<Int: IntHash, const N: usize, S: Default> IntSmallSet<Int, N, S> {
+    /// Empty set.
+    #[inline]
+    pub fn new() -> Self {
+        IntSmallSet {
+            repr: Repr::Inline {
+                len: 0,
+                elems: None,
+                hasher: S::default(),
+            },
+        }
+    }
+}
+
+impl<Int: IntHash, const N: usize, S> IntSmallSet<Int, N, S> {
+    /// Number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self.repr {
+            Repr::Inline { len, .. } => len,
+            Repr::Spilled(ref set) => set.len(),
+        }
+    }
+    /// True if the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// True if the elements have moved to a hash set.
+    #[inline]
+    pub fn is_spilled(&self) -> bool {
+        match self.repr {
+            Repr::Inline { .. } => false,
+            Repr::Spilled(_) => true,
+        }
+    }
+    /// Number of elements the set can hold without reallocating, `N` while it is inline.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match self.repr {
+            Repr::Inline { .. } => N,
+            Repr::Spilled(ref set) => set.capacity(),
+        }
+    }
+    /// The hasher of the set.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        match self.repr {
+            Repr::Inline { ref hasher, .. } => hasher,
+            Repr::Spilled(ref set) => set.hasher(),
+        }
+    }
+    /// Iterates over the elements.
+    #[inline]
+    pub fn iter(&self) -> IntSmallSetIter<'_, Int> {
+        match self.repr {
+            Repr::Inline {
+                len,
+                elems: Some(ref elems),
+                ..
+            } => IntSmallSetIter::Inline(elems[..len].iter()),
+            Repr::Inline { elems: None, .. } => IntSmallSetIter::Inline([].iter()),
+            Repr::Spilled(ref set) => IntSmallSetIter::Spilled(set.iter()),
+        }
+    }
+}
+
+impl<Int: IntHash + Ord, const N: usize, S> IntSmallSet<Int, N, S> {
+    /// Iterates over the elements in increasing order.
+    ///
+    /// Inline elements are already sorted. Spilled elements are iterated over with
+    /// [`IntHSet::iter_sorted`](struct.IntHSet.html#method.iter_sorted), which allocates unless
+    /// the hash table already yields them in increasing order.
+    pub fn iter_sorted(&self) -> IntSmallSetSortedIter<'_, Int> {
+        let iter = match self.repr {
+            Repr::Inline {
+                len,
+                elems: Some(ref elems),
+                ..
+            } => SortedRepr::Inline(elems[..len].iter()),
+            Repr::Inline { elems: None, .. } => SortedRepr::Inline([].iter()),
+            Repr::Spilled(ref set) => SortedRepr::Spilled(set.iter_sorted()),
+        };
+        IntSmallSetSortedIter { iter }
+    }
+}
+
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> IntSmallSet<Int, N, S> {
+    /// Empty set, already spilled if `capa > N`.
+    pub fn with_capacity(capa: usize) -> Self {
+        if capa > N {
+            IntSmallSet {
+                repr: Repr::Spilled(IntHSet::with_capacity(capa)),
+            }
+        } else {
+            Self::new()
+        }
+    }
+    /// Moves the elements to a hash set with room for at least `capa` elements, if they are not
+    /// there already.
+    fn spill(&mut self, capa: usize) -> &mut IntHSet<Int, S> {
+        if let Repr::Inline {
+            len,
+            elems,
+            ref mut hasher,
+        } = self.repr
+        {
+            let capa = ::std::cmp::max(capa, len);
+            let mut set = HashSet::with_capacity_and_hasher(capa, ::std::mem::take(hasher));
+            if let Some(elems) = elems {
+                set.extend(elems[..len].iter().copied())
+            }
+            self.repr = Repr::Spilled(IntHSet { set })
+        }
+        match self.repr {
+            Repr::Spilled(ref mut set) => set,
+            Repr::Inline { .. } => panic!("[bug] small set still inline after spilling"),
+        }
+    }
+
+    /// True if the set contains an element.
+    #[inline]
+    pub fn contains(&self, elem: &Int) -> bool {
+        self.get(elem).is_some()
+    }
+    /// Reference to the element of the set equal to `elem`, if any.
+    pub fn get(&self, elem: &Int) -> Option<&Int> {
+        match self.repr {
+            Repr::Inline {
+                len,
+                elems: Some(ref elems),
+                ..
+            } => elems[..len].binary_search(elem).ok().map(|pos| &elems[pos]),
+            Repr::Inline { elems: None, .. } => None,
+            Repr::Spilled(ref set) => set.get(elem),
+        }
+    }
+    /// Inserts an element, returns `true` if it was not there.
+    pub fn insert(&mut self, elem: Int) -> bool {
+        match self.repr {
+            Repr::Inline {
+                ref mut len,
+                elems: ref mut elems @ None,
+                ..
+            } if N > 0 => {
+                *elems = Some([elem; N]);
+                *len = 1;
+                return true;
+            }
+            Repr::Inline {
+                ref mut len,
+                elems: Some(ref mut elems),
+                ..
+            } => match elems[..*len].binary_search(&elem) {
+                Ok(_) => return false,
+                Err(pos) if *len < N => {
+                    elems.copy_within(pos..*len, pos + 1);
+                    elems[pos] = elem;
+                    *len += 1;
+                    return true;
+                }
+                Err(_) => (),
+            },
+            Repr::Spilled(ref mut set) => return set.insert(elem),
+            Repr::Inline { elems: None, .. } => (),
+        }
+        self.spill(2 * N).insert(elem)
+    }
+    /// Inserts an element, returns the element equal to it that it replaced, if any.
+    pub fn replace(&mut self, elem: Int) -> Option<Int> {
+        match self.repr {
+            Repr::Inline {
+                len,
+                elems: Some(ref mut elems),
+                ..
+            } => {
+                if let Ok(pos) = elems[..len].binary_search(&elem) {
+                    return Some(::std::mem::replace(&mut elems[pos], elem));
+                }
+            }
+            Repr::Spilled(ref mut set) => return set.replace(elem),
+            Repr::Inline { elems: None, .. } => (),
+        }
+        self.insert(elem);
+        None
+    }
+    /// Removes an element, returns `true` if it was there.
+    #[inline]
+    pub fn remove(&mut self, elem: &Int) -> bool {
+        self.take(elem).is_some()
+    }
+    /// Removes an element and returns it, if it was there.
+    pub fn take(&mut self, elem: &Int) -> Option<Int> {
+        match self.repr {
+            Repr::Inline {
+                ref mut len,
+                elems: Some(ref mut elems),
+                ..
+            } => {
+                let pos = elems[..*len].binary_search(elem).ok()?;
+                let taken = elems[pos];
+                elems.copy_within(pos + 1..*len, pos);
+                *len -= 1;
+                Some(taken)
+            }
+            Repr::Inline { elems: None, .. } => None,
+            Repr::Spilled(ref mut set) => set.take(elem),
+        }
+    }
+    /// Reference to the element of the set equal to `elem`, inserts the result of `f` applied to
+    /// `elem` if there is none.
+    pub fn get_or_insert_with<F: FnOnce(&Int) -> Int>(&mut self, elem: &Int, f: F) -> &Int {
+        if !self.contains(elem) {
+            self.insert(f(elem));
+        }
+        self.get(elem)
+            .expect("[illegal] `get_or_insert_with` produced an element different from its input")
+    }
+    /// Reserves room for at least `additional` more elements, spills if they do not fit inline.
+    pub fn reserve(&mut self, additional: usize) {
+        match self.repr {
+            Repr::Inline { len, .. } => {
+                if len + additional > N {
+                    self.spill(len + additional);
+                }
+            }
+            Repr::Spilled(ref mut set) => set.reserve(additional),
+        }
+    }
+    /// Shrinks the capacity of the set as much as possible, does not move it back inline.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        if let Repr::Spilled(ref mut set) = self.repr {
+            set.shrink_to_fit()
+        }
+    }
+    /// Removes all the elements.
+    pub fn clear(&mut self) {
+        match self.repr {
+            Repr::Inline { ref mut len, .. } => *len = 0,
+            Repr::Spilled(ref mut set) => set.clear(),
+        }
+    }
+    /// Removes all the elements and iterates over them.
+    pub fn drain(&mut self) -> IntSmallSetDrain<'_, Int, N> {
+        let iter = match self.repr {
+            Repr::Inline {
+                ref mut len, elems, ..
+            } => {
+                let drained = elems.map(|elems| IntoIterator::into_iter(elems).take(*len));
+                *len = 0;
+                DrainRepr::Inline(drained)
+            }
+            Repr::Spilled(ref mut set) => DrainRepr::Spilled(set.drain()),
+        };
+        IntSmallSetDrain { iter }
+    }
+    /// Only keeps the elements verifying a predicate.
+    pub fn retain<F: FnMut(&Int) -> bool>(&mut self, mut keep: F) {
+        match self.repr {
+            Repr::Inline {
+                ref mut len,
+                elems: Some(ref mut elems),
+                ..
+            } => {
+                let mut kept = 0;
+                for pos in 0..*len {
+                    let elem = elems[pos];
+                    if keep(&elem) {
+                        elems[kept] = elem;
+                        kept += 1
+                    }
+                }
+                *len = kept
+            }
+            Repr::Inline { elems: None, .. } => (),
+            Repr::Spilled(ref mut set) => set.retain(keep),
+        }
+    }
+    /// The elements in increasing order.
+    pub fn into_sorted_vec(self) -> Vec<Int> {
+        let mut elems: Vec<_> = self.iter().copied().collect();
+        if self.is_spilled() {
+            elems.sort_unstable()
+        }
+        elems
+    }
+
+    /// Adds all the elements of `other` to `self`.
+    pub fn union_with(&mut self, other: &Self) {
+        self.extend(other.iter())
+    }
+    /// Removes the elements of `self` that are not in `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.retain(|elem| other.contains(elem))
+    }
+    /// Removes the elements of `other` from `self`.
+    pub fn difference_with(&mut self, other: &Self) {
+        self.retain(|elem| !other.contains(elem))
+    }
+    /// Keeps the elements that are in exactly one of `self` and `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        for elem in other {
+            if !self.remove(elem) {
+                self.insert(*elem);
+            }
+        }
+    }
+
+    /// True if all the elements of `self` are in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|elem| other.contains(elem))
+    }
+    /// True if all the elements of `other` are in `self`.
+    #[inline]
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+    /// True if `self` and `other` have no element in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small.iter().all(|elem| !big.contains(elem))
+    }
+}
+
+impl<Int: IntHash + Copy, const N: usize, S: Clone> Clone for IntSmallSet<Int, N, S> {
+    fn clone(&self) -> Self {
+        let repr = match self.repr {
+            Repr::Inline {
+                len,
+                elems,
+                ref hasher,
+            } => Repr::Inline {
+                len,
+                elems,
+                hasher: hasher.clone(),
+            },
+            Repr::Spilled(ref set) => Repr::Spilled(set.clone()),
+        };
+        IntSmallSet { repr }
+    }
+}
+impl<Int: IntHash, const N: usize, S: Default> Default for IntSmallSet<Int, N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<Int: IntHash + ::std::fmt::Debug, const N: usize, S> ::std::fmt::Debug
+    for IntSmallSet<Int, N, S>
+{
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> PartialEq
+    for IntSmallSet<Int, N, S>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> Eq
+    for IntSmallSet<Int, N, S>
+{
+}
+impl<Int: IntHash, const N: usize, S> Hash for IntSmallSet<Int, N, S> {
+    /// Independent of the representation and iteration order, consistent with `PartialEq`.
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: ::std::hash::Hasher,
+    {
+        state.write_u64(unordered_hash(self.iter()))
+    }
+}
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> PartialOrd
+    for IntSmallSet<Int, N, S>
+{
+    /// Compares the sorted elements, lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> Ord
+    for IntSmallSet<Int, N, S>
+{
+    /// Compares the sorted elements, lexicographically.
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.iter_sorted().cmp(other.iter_sorted())
+    }
+}
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> Extend<Int>
+    for IntSmallSet<Int, N, S>
+{
+    fn extend<It: IntoIterator<Item = Int>>(&mut self, iter: It) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+impl<'a, Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default> Extend<&'a Int>
+    for IntSmallSet<Int, N, S>
+{
+    fn extend<It: IntoIterator<Item = &'a Int>>(&mut self, iter: It) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+impl<Int: IntHash + Ord + Copy, const N: usize, S: BuildHasher + Default>
+    ::std::iter::FromIterator<Int> for IntSmallSet<Int, N, S>
+{
+    fn from_iter<It: IntoIterator<Item = Int>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+impl<'a, Int: IntHash, const N: usize, S> IntoIterator for &'a IntSmallSet<Int, N, S> {
+    type Item = &'a Int;
+    type IntoIter = IntSmallSetIter<'a, Int>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<Int: IntHash + Copy, const N: usize, S> IntoIterator for IntSmallSet<Int, N, S> {
+    type Item = Int;
+    type IntoIter = IntSmallSetIntoIter<Int, N>;
+    fn into_iter(self) -> Self::IntoIter {
+        let iter = match self.repr {
+            Repr::Inline { len, elems, .. } => {
+                IntoIterRepr::Inline(elems.map(|elems| IntoIterator::into_iter(elems).take(len)))
+            }
+            Repr::Spilled(set) => IntoIterRepr::Spilled(set.into_iter()),
+        };
+        IntSmallSetIntoIter { iter }
+    }
+}
+
+impl<'a, Int, const N: usize, S> ::std::ops::BitOr<&'a IntSmallSet<Int, N, S>>
+    for &'a IntSmallSet<Int, N, S>
+where
+    Int: IntHash + Ord + Copy,
+    S: BuildHasher + Default + Clone,
+{
+    type Output = IntSmallSet<Int, N, S>;
+    /// Union.
+    fn bitor(self, other: &'a IntSmallSet<Int, N, S>) -> IntSmallSet<Int, N, S> {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut res = big.clone();
+        res.union_with(small);
+        res
+    }
+}
+impl<'a, Int, const N: usize, S> ::std::ops::BitAnd<&'a IntSmallSet<Int, N, S>>
+    for &'a IntSmallSet<Int, N, S>
+where
+    Int: IntHash + Ord + Copy,
+    S: BuildHasher + Default + Clone,
+{
+    type Output = IntSmallSet<Int, N, S>;
+    /// Intersection.
+    fn bitand(self, other: &'a IntSmallSet<Int, N, S>) -> IntSmallSet<Int, N, S> {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small
+            .iter()
+            .filter(|elem| big.contains(elem))
+            .copied()
+            .collect()
+    }
+}
+impl<'a, Int, const N: usize, S> ::std::ops::BitXor<&'a IntSmallSet<Int, N, S>>
+    for &'a IntSmallSet<Int, N, S>
+where
+    Int: IntHash + Ord + Copy,
+    S: BuildHasher + Default + Clone,
+{
+    type Output = IntSmallSet<Int, N, S>;
+    /// Symmetric difference.
+    fn bitxor(self, other: &'a IntSmallSet<Int, N, S>) -> IntSmallSet<Int, N, S> {
+        let mut res = self.clone();
+        res.symmetric_difference_with(other);
+        res
+    }
+}
+impl<'a, Int, const N: usize, S> ::std::ops::Sub<&'a IntSmallSet<Int, N, S>>
+    for &'a IntSmallSet<Int, N, S>
+where
+    Int: IntHash + Ord + Copy,
+    S: BuildHasher + Default + Clone,
+{
+    type Output = IntSmallSet<Int, N, S>;
+    /// Difference.
+    fn sub(self, other: &'a IntSmallSet<Int, N, S>) -> IntSmallSet<Int, N, S> {
+        self.iter()
+            .filter(|elem| !other.contains(elem))
+            .copied()
+            .collect()
+    }
+}
+
+/// Iterator over the elements of an [`IntSmallSet`](struct.IntSmallSet.html).
+pub enum IntSmallSetIter<'a, Int> {
+    /// Inline elements.
+    Inline(::std::slice::Iter<'a, Int>),
+    /// Spilled elements.
+    Spilled(::std::collections::hash_set::Iter<'a, Int>),
+}
+impl<'a, Int> Iterator for IntSmallSetIter<'a, Int> {
+    type Item = &'a Int;
+    #[inline]
+    fn next(&mut self) -> Option<&'a Int> {
+        match self {
+            IntSmallSetIter::Inline(iter) => iter.next(),
+            IntSmallSetIter::Spilled(iter) => iter.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            IntSmallSetIter::Inline(iter) => iter.size_hint(),
+            IntSmallSetIter::Spilled(iter) => iter.size_hint(),
+        }
+    }
+}
+impl<'a, Int> ExactSizeIterator for IntSmallSetIter<'a, Int> {}
+
+/// Actual iterator of an [`IntSmallSetSortedIter`](struct.IntSmallSetSortedIter.html).
+#[derive(Clone, Debug)]
+enum SortedRepr<'a, Int> {
+    /// Inline elements, sorted.
+    Inline(::std::slice::Iter<'a, Int>),
+    /// Spilled elements.
+    Spilled(IntHSetSortedIter<'a, Int>),
+}
+
+/// Iterator over the elements of an [`IntSmallSet`](struct.IntSmallSet.html) in increasing
+/// order, see [`iter_sorted`](struct.IntSmallSet.html#method.iter_sorted).
+#[derive(Clone, Debug)]
+pub struct IntSmallSetSortedIter<'a, Int> {
+    /// Actual iterator.
+    iter: SortedRepr<'a, Int>,
+}
+impl<'a, Int> Iterator for IntSmallSetSortedIter<'a, Int> {
+    type Item = &'a Int;
+    #[inline]
+    fn next(&mut self) -> Option<&'a Int> {
+        match self.iter {
+            SortedRepr::Inline(ref mut iter) => iter.next(),
+            SortedRepr::Spilled(ref mut iter) => iter.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.iter {
+            SortedRepr::Inline(ref iter) => iter.size_hint(),
+            SortedRepr::Spilled(ref iter) => iter.size_hint(),
+        }
+    }
+}
+impl<'a, Int> ExactSizeIterator for IntSmallSetSortedIter<'a, Int> {}
+
+/// Inline elements of an owning or draining iterator, `None` if the set never had any.
+type InlineIntoIter<Int, const N: usize> = Option<Take<::std::array::IntoIter<Int, N>>>;
+
+/// Owning iterator over the elements of an [`IntSmallSet`](struct.IntSmallSet.html).
+pub struct IntSmallSetIntoIter<Int, const N: usize> {
+    /// Actual iterator.
+    iter: IntoIterRepr<Int, N>,
+}
+/// Representation of an [`IntSmallSetIntoIter`](struct.IntSmallSetIntoIter.html).
+enum IntoIterRepr<Int, const N: usize> {
+    /// Inline elements.
+    Inline(InlineIntoIter<Int, N>),
+    /// Spilled elements.
+    Spilled(::std::collections::hash_set::IntoIter<Int>),
+}
+impl<Int, const N: usize> Iterator for IntSmallSetIntoIter<Int, N> {
+    type Item = Int;
+    #[inline]
+    fn next(&mut self) -> Option<Int> {
+        match self.iter {
+            IntoIterRepr::Inline(ref mut iter) => iter.as_mut()?.next(),
+            IntoIterRepr::Spilled(ref mut iter) => iter.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.iter {
+            IntoIterRepr::Inline(ref iter) => {
+                iter.as_ref().map_or((0, Some(0)), Iterator::size_hint)
+            }
+            IntoIterRepr::Spilled(ref iter) => iter.size_hint(),
+        }
+    }
+}
+impl<Int, const N: usize> ExactSizeIterator for IntSmallSetIntoIter<Int, N> {}
+
+/// Draining iterator over the elements of an [`IntSmallSet`](struct.IntSmallSet.html).
+pub struct IntSmallSetDrain<'a, Int, const N: usize> {
+    /// Actual iterator.
+    iter: DrainRepr<'a, Int, N>,
+}
+/// Representation of an [`IntSmallSetDrain`](struct.IntSmallSetDrain.html).
+enum DrainRepr<'a, Int, const N: usize> {
+    /// Inline elements, copied out of the set.
+    Inline(InlineIntoIter<Int, N>),
+    /// Spilled elements.
+    Spilled(::std::collections::hash_set::Drain<'a, Int>),
+}
+impl<'a, Int, const N: usize> Iterator for IntSmallSetDrain<'a, Int, N> {
+    type Item = Int;
+    #[inline]
+    fn next(&mut self) -> Option<Int> {
+        match self.iter {
+            DrainRepr::Inline(ref mut iter) => iter.as_mut()?.next(),
+            DrainRepr::Spilled(ref mut iter) => iter.next(),
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.iter {
+            DrainRepr::Inline(ref iter) => iter.as_ref().map_or((0, Some(0)), Iterator::size_hint),
+            DrainRepr::Spilled(ref iter) => iter.size_hint(),
+        }
+    }
+}
+impl<'a, Int, const N: usize> ExactSizeIterator for IntSmallSetDrain<'a, Int, N> {}
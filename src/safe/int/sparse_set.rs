@@ -0,0 +1,204 @@
+//! Sparse sets of wrapped indices.
+
+use super::{IndexVec, IntWrap};
+
+/// Set of wrapped indices with constant-time insertion, removal, membership and clear.
+///
+/// This is what the `sparse set` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as
+/// a type alias. Elements are stored in a dense vector, and a sparse
+/// [`IndexVec`](struct.IndexVec.html) gives the position of each index in the dense vector.
+/// Clearing only forgets the dense vector, the sparse one is never reset. Memory is linear in the
+/// largest index ever inserted.
+///
+/// Iteration follows insertion order. [`remove`](#method.remove) moves the last element to the
+/// position of the removed one, use [`shift_remove`](#method.shift_remove) (linear) to preserve
+/// the order.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Sparse set of variable indices."]
+///     sparse set: VarSparseSet
+/// }
+/// fn main() {
+///     let mut seen = VarSparseSet::new() ;
+///     let (v_0, v_3, v_7) = ( VarIndex::new(0), VarIndex::new(3), VarIndex::new(7) ) ;
+///     assert!( seen.insert(v_7) ) ;
+///     assert!( seen.insert(v_0) ) ;
+///     assert!( seen.insert(v_3) ) ;
+///     assert!( ! seen.insert(v_0) ) ;
+///     assert_eq!( seen.as_slice(), & [v_7, v_0, v_3] ) ;
+///     assert!( seen.shift_remove(& v_7) ) ;
+///     assert_eq!( seen.as_slice(), & [v_0, v_3] ) ;
+///     assert!( seen.remove(& v_0) ) ;
+///     assert_eq!( seen.as_slice(), & [v_3] ) ;
+///     seen.clear() ;
+///     assert!( ! seen.contains(& v_3) ) ;
+///     assert!( seen.is_empty() ) ;
+/// }
+/// ```
+#[derive(Clone)]
+pub struct IntSparseSet<I> {
+    /// Elements, in insertion order.
+    dense: Vec<I>,
+    /// Position of each index in `dense`, meaningless for indices not in the set.
+    sparse: IndexVec<I, usize>,
+}
+
+impl<I> Default for IntSparseSet<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> IntSparseSet<I> {
+    /// Empty set.
+    #[inline]
+    pub fn new() -> Self {
+        IntSparseSet {
+            dense: Vec::new(),
+            sparse: IndexVec::new(),
+        }
+    }
+    /// Empty set with room for indices up to `capacity` (excluded).
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        IntSparseSet {
+            dense: Vec::with_capacity(capacity),
+            sparse: IndexVec::of_elems(0, capacity),
+        }
+    }
+    /// Number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+    /// True if the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+    /// Removes all the elements, in constant time.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.dense.clear()
+    }
+    /// Iterates over the elements, in insertion order.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, I> {
+        self.dense.iter()
+    }
+    /// The elements, in insertion order.
+    #[inline]
+    pub fn as_slice(&self) -> &[I] {
+        &self.dense
+    }
+}
+
+impl<I: IntWrap + From<usize> + Copy> IntSparseSet<I> {
+    /// Position of an element in the dense vector, `None` if it is not in the set.
+    #[inline]
+    fn position(&self, elem: &I) -> Option<usize> {
+        let pos = *self.sparse.get(*elem)?;
+        match self.dense.get(pos) {
+            Some(e) if e.inner() == elem.inner() => Some(pos),
+            _ => None,
+        }
+    }
+    /// True if an index is in the set.
+    #[inline]
+    pub fn contains(&self, elem: &I) -> bool {
+        self.position(elem).is_some()
+    }
+    /// Inserts an index, returns `true` if it was not there.
+    pub fn insert(&mut self, elem: I) -> bool {
+        if self.contains(&elem) {
+            return false;
+        }
+        if self.sparse.len() <= elem.inner() {
+            self.sparse.resize(elem.inner() + 1, 0)
+        }
+        self.sparse[elem] = self.dense.len();
+        self.dense.push(elem);
+        true
+    }
+    /// Removes an index in constant time, returns `true` if it was there.
+    ///
+    /// The last element takes the place of the removed one in the iteration order.
+    pub fn remove(&mut self, elem: &I) -> bool {
+        match self.position(elem) {
+            Some(pos) => {
+                self.dense.swap_remove(pos);
+                if let Some(moved) = self.dense.get(pos) {
+                    self.sparse[*moved] = pos
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    /// Removes an index preserving the order of the other elements, returns `true` if it was
+    /// there.
+    ///
+    /// Linear in the number of elements inserted after the removed one.
+    pub fn shift_remove(&mut self, elem: &I) -> bool {
+        match self.position(elem) {
+            Some(pos) => {
+                self.dense.remove(pos);
+                for (pos, moved) in self.dense.iter().enumerate().skip(pos) {
+                    self.sparse[*moved] = pos
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    /// Removes the last element inserted.
+    #[inline]
+    pub fn pop(&mut self) -> Option<I> {
+        self.dense.pop()
+    }
+}
+
+impl<I: ::std::fmt::Debug> ::std::fmt::Debug for IntSparseSet<I> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        fmt.debug_set().entries(self.dense.iter()).finish()
+    }
+}
+impl<I: IntWrap + From<usize> + Copy> PartialEq for IntSparseSet<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|elem| other.contains(elem))
+    }
+}
+impl<I: IntWrap + From<usize> + Copy> Eq for IntSparseSet<I> {}
+impl<'a, I> IntoIterator for &'a IntSparseSet<I> {
+    type Item = &'a I;
+    type IntoIter = ::std::slice::Iter<'a, I>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.dense.iter()
+    }
+}
+impl<I> IntoIterator for IntSparseSet<I> {
+    type Item = I;
+    type IntoIter = ::std::vec::IntoIter<I>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.dense.into_iter()
+    }
+}
+impl<I: IntWrap + From<usize> + Copy> Extend<I> for IntSparseSet<I> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+impl<I: IntWrap + From<usize> + Copy> ::std::iter::FromIterator<I> for IntSparseSet<I> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
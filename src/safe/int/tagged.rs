@@ -0,0 +1,369 @@
+//! Vector checking, in debug, that the indices used to access it come from it.
+
+use std::ops::{Index, IndexMut};
+
+use super::{IndexVec, IntHash, IntRange, IntWrap};
+
+/// Source of fresh instance tags, only used in debug.
+#[cfg(debug_assertions)]
+static NEXT_TAG: ::std::sync::atomic::AtomicU32 = ::std::sync::atomic::AtomicU32::new(1);
+
+/// A fresh instance tag.
+#[cfg(debug_assertions)]
+fn fresh_tag() -> u32 {
+    NEXT_TAG.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Wrapped index remembering, in debug, which [`TaggedVec`](struct.TaggedVec.html) created it.
+///
+/// In release, this is exactly an `I`. Comparison and hashing ignore the tag.
+#[derive(Clone, Copy)]
+pub struct Tagged<I> {
+    /// Actual index.
+    index: I,
+    /// Tag of the map the index comes from.
+    #[cfg(debug_assertions)]
+    tag: u32,
+}
+impl<I: Copy> Tagged<I> {
+    /// The index, without its tag.
+    #[inline]
+    pub fn index(&self) -> I {
+        self.index
+    }
+}
+impl<I: ::std::fmt::Debug> ::std::fmt::Debug for Tagged<I> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.index.fmt(fmt)
+    }
+}
+impl<I: ::std::fmt::Display> ::std::fmt::Display for Tagged<I> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.index.fmt(fmt)
+    }
+}
+impl<I: PartialEq> PartialEq for Tagged<I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+impl<I: Eq> Eq for Tagged<I> {}
+impl<I: PartialOrd> PartialOrd for Tagged<I> {
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        self.index.partial_cmp(&other.index)
+    }
+}
+impl<I: Ord> Ord for Tagged<I> {
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+impl<I: ::std::hash::Hash> ::std::hash::Hash for Tagged<I> {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+impl<I: IntHash> IntHash for Tagged<I> {}
+
+/// Vector indexed by wrapped indices that remember, in debug, which map they come from.
+///
+/// This is what the `tagged map` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to,
+/// as a type alias. In debug, each map has a unique instance tag, and the
+/// [`Tagged`](struct.Tagged.html) indices it hands out carry it. Accessing a map with an index
+/// from another map panics. In release, a `TaggedVec<I, T>` is exactly an `IndexVec<I, T>`, and
+/// a `Tagged<I>` is exactly an `I`.
+///
+/// Clones share the tag of the original map. Tagged indices only come from the map itself:
+/// [`push`](#method.push), [`push_with`](#method.push_with), [`next_index`](#method.next_index),
+/// [`indices`](#method.indices) and [`index_iter`](#method.index_iter). There is no access through
+/// raw indices, use [`into_index_vec`](#method.into_index_vec) to get an unchecked
+/// [`IndexVec`](struct.IndexVec.html).
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Vector indexed by variable indices, with provenance checks in debug."]
+///     tagged map: VarTaggedMap
+/// }
+/// fn main() {
+///     let mut solver_a: VarTaggedMap<& str> = VarTaggedMap::new() ;
+///     let mut solver_b: VarTaggedMap<& str> = VarTaggedMap::new() ;
+///     let a_x = solver_a.push("x") ;
+///     let b_y = solver_b.push("y") ;
+///     assert_eq!( solver_a[a_x], "x" ) ;
+///     assert!( solver_b.owns(& b_y) ) ;
+///     // Only false in debug.
+///     if ! solver_b.owns(& a_x) {
+///         let res = std::panic::catch_unwind( || solver_b[a_x] ) ;
+///         assert!( res.is_err() )
+///     }
+/// }
+/// ```
+///
+/// In debug, all the accesses check the provenance of the indices.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Vector indexed by variable indices, with provenance checks in debug."]
+///     tagged map: VarTaggedMap
+/// }
+/// fn main() {
+///     use std::panic::{ catch_unwind, AssertUnwindSafe } ;
+///     let mut solver_a: VarTaggedMap<u32> = VarTaggedMap::new() ;
+///     let mut solver_b: VarTaggedMap<u32> = VarTaggedMap::new() ;
+///     let a_x = solver_a.push(0) ;
+///     let b_x = solver_b.push(1) ;
+///     let b_y = solver_b.push(2) ;
+///     assert_eq!( solver_a.next_index().index(), b_y.index() ) ;
+///
+///     let accesses: Vec< Box<dyn FnMut(& mut VarTaggedMap<u32>)> > = vec![
+///         Box::new( |b| { b[a_x] ; } ),
+///         Box::new( |b| b[a_x] = 7 ),
+///         Box::new( |b| { b.get(a_x) ; } ),
+///         Box::new( |b| { b.get_mut(a_x) ; } ),
+///         Box::new( |b| b.swap(a_x, b_y) ),
+///         Box::new( |b| b.swap(b_x, a_x) ),
+///     ] ;
+///     // Only false in debug.
+///     let checked = ! solver_b.owns(& a_x) ;
+///     for mut access in accesses {
+///         let res = catch_unwind( AssertUnwindSafe( || access(& mut solver_b) ) ) ;
+///         assert_eq!( res.is_err(), checked )
+///     }
+///     // Indices from `solver_a`'s own API are fine.
+///     let indices: Vec<_> = solver_a.indices().collect() ;
+///     for index in indices {
+///         assert!( solver_a.owns(& index) ) ;
+///         solver_a[index] += 1
+///     }
+///     assert!( solver_a.index_iter().all( |(index, _)| solver_a.owns(& index) ) ) ;
+///     let a_y = solver_a.push_with( |index| index.index().get() as u32 ) ;
+///     assert_eq!( solver_a.get(a_y), Some(& 1) ) ;
+///     assert_eq!( solver_a.as_slice(), & [1, 1] ) ;
+/// }
+/// ```
+pub struct TaggedVec<I, T> {
+    /// Elements.
+    vec: IndexVec<I, T>,
+    /// Instance tag.
+    #[cfg(debug_assertions)]
+    tag: u32,
+}
+
+impl<I, T> Default for TaggedVec<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<I, T: Clone> Clone for TaggedVec<I, T> {
+    fn clone(&self) -> Self {
+        TaggedVec {
+            vec: self.vec.clone(),
+            #[cfg(debug_assertions)]
+            tag: self.tag,
+        }
+    }
+}
+impl<I, T: ::std::fmt::Debug> ::std::fmt::Debug for TaggedVec<I, T> {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.vec.fmt(fmt)
+    }
+}
+
+impl<I, T> TaggedVec<I, T> {
+    /// Creates a map from an existing vector, with a fresh tag.
+    #[inline]
+    pub fn of(vec: IndexVec<I, T>) -> Self {
+        TaggedVec {
+            vec,
+            #[cfg(debug_assertions)]
+            tag: fresh_tag(),
+        }
+    }
+    /// Creates an empty map, with a fresh tag.
+    #[inline]
+    pub fn new() -> Self {
+        Self::of(IndexVec::new())
+    }
+    /// Creates an empty map with some capacity, with a fresh tag.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::of(IndexVec::with_capacity(capacity))
+    }
+    /// Turns the map into an untagged map.
+    #[inline]
+    pub fn into_index_vec(self) -> IndexVec<I, T> {
+        self.vec
+    }
+    /// Pops an element.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    /// Clears the map, the tag does not change.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    /// Number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    /// True if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    /// Iterates over the elements.
+    #[inline]
+    pub fn iter(&self) -> ::std::slice::Iter<'_, T> {
+        self.vec.iter()
+    }
+    /// Iterates over the elements (mutable version).
+    #[inline]
+    pub fn iter_mut(&mut self) -> ::std::slice::IterMut<'_, T> {
+        self.vec.iter_mut()
+    }
+    /// The elements, as an untyped slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice().raw()
+    }
+
+    /// Tags an index with the tag of this map.
+    #[inline]
+    fn tag(&self, index: I) -> Tagged<I> {
+        Tagged {
+            index,
+            #[cfg(debug_assertions)]
+            tag: self.tag,
+        }
+    }
+    /// True if an index comes from this map (or a clone of it). Always true in release.
+    #[inline]
+    pub fn owns(&self, _index: &Tagged<I>) -> bool {
+        #[cfg(debug_assertions)]
+        {
+            _index.tag == self.tag
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            true
+        }
+    }
+    /// Checks that an index comes from this map, only active in debug.
+    #[inline]
+    fn check(&self, index: Tagged<I>) -> I {
+        debug_assert! {
+            self.owns(&index),
+            "[illegal] accessing a tagged map with an index from another map"
+        }
+        index.index
+    }
+}
+
+impl<I: IntWrap + From<usize>, T> TaggedVec<I, T> {
+    /// The next free index.
+    #[inline]
+    pub fn next_index(&self) -> Tagged<I> {
+        self.tag(self.vec.next_index())
+    }
+    /// Pushes an element, returns its index.
+    #[inline]
+    pub fn push(&mut self, elem: T) -> Tagged<I> {
+        let index = self.vec.push_idx(elem);
+        self.tag(index)
+    }
+    /// Pushes the element produced by `f` applied to the index of this element, returns the
+    /// index.
+    #[inline]
+    pub fn push_with<F: FnOnce(Tagged<I>) -> T>(&mut self, f: F) -> Tagged<I> {
+        let elem = f(self.next_index());
+        self.push(elem)
+    }
+    /// Reference to an element, `None` if the index is out of bounds.
+    ///
+    /// In debug, panics if the index comes from another map.
+    #[inline]
+    pub fn get(&self, index: Tagged<I>) -> Option<&T> {
+        self.vec.get(self.check(index))
+    }
+    /// Mutable reference to an element, `None` if the index is out of bounds.
+    ///
+    /// In debug, panics if the index comes from another map.
+    #[inline]
+    pub fn get_mut(&mut self, index: Tagged<I>) -> Option<&mut T> {
+        let index = self.check(index);
+        self.vec.get_mut(index)
+    }
+    /// Swaps two elements.
+    ///
+    /// In debug, panics if an index comes from another map.
+    #[inline]
+    pub fn swap(&mut self, a: Tagged<I>, b: Tagged<I>) {
+        let (a, b) = (self.check(a), self.check(b));
+        self.vec.swap(a, b)
+    }
+    /// Iterates over the elements with their tagged index.
+    pub fn index_iter(&self) -> impl Iterator<Item = (Tagged<I>, &T)> {
+        self.vec
+            .index_iter()
+            .map(move |(index, elem)| (self.tag(index), elem))
+    }
+    /// Iterates over the elements with their tagged index, mutable version.
+    pub fn index_iter_mut(&mut self) -> impl Iterator<Item = (Tagged<I>, &mut T)> {
+        #[cfg(debug_assertions)]
+        let tag = self.tag;
+        self.vec.index_iter_mut().map(move |(index, elem)| {
+            let index = Tagged {
+                index,
+                #[cfg(debug_assertions)]
+                tag,
+            };
+            (index, elem)
+        })
+    }
+    /// Iterates over the tagged indices of the map.
+    pub fn indices(&self) -> impl Iterator<Item = Tagged<I>> + '_ {
+        let range: IntRange<I> = self.vec.indices();
+        range.map(move |index| self.tag(index))
+    }
+}
+
+impl<I: IntWrap, T> Index<Tagged<I>> for TaggedVec<I, T> {
+    type Output = T;
+    fn index(&self, index: Tagged<I>) -> &T {
+        &self.vec[self.check(index)]
+    }
+}
+impl<I: IntWrap, T> IndexMut<Tagged<I>> for TaggedVec<I, T> {
+    fn index_mut(&mut self, index: Tagged<I>) -> &mut T {
+        let index = self.check(index);
+        &mut self.vec[index]
+    }
+}
+impl<I, T> ::std::iter::FromIterator<T> for TaggedVec<I, T> {
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        Self::of(iter.into_iter().collect())
+    }
+}
+impl<I, T> Extend<T> for TaggedVec<I, T> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.vec.extend(iter)
+    }
+}
+impl<'a, I, T> IntoIterator for &'a TaggedVec<I, T> {
+    type Item = &'a T;
+    type IntoIter = ::std::slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
@@ -0,0 +1,568 @@
+//! Backtrackable collections recording undo entries on a trail.
+
+use std::hash::BuildHasher;
+use std::ops::Deref;
+
+use super::{BuildHashUsize, IndexVec, IntHMap, IntHSet, IntHash, IntWrap};
+
+/// Undo entries and checkpoints of a backtrackable collection.
+#[derive(Clone, Debug)]
+struct Trail<U> {
+    /// Undo entries, most recent last.
+    undo: Vec<U>,
+    /// Length of the trail when each active checkpoint was pushed.
+    checkpoints: Vec<usize>,
+}
+impl<U> Trail<U> {
+    /// Empty trail.
+    fn new() -> Self {
+        Trail {
+            undo: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+    /// Number of active checkpoints.
+    fn depth(&self) -> usize {
+        self.checkpoints.len()
+    }
+    /// Records an undo entry, if there is an active checkpoint.
+    #[inline]
+    fn record(&mut self, undo: U) {
+        if !self.checkpoints.is_empty() {
+            self.undo.push(undo)
+        }
+    }
+    /// True if mutations need to be recorded.
+    #[inline]
+    fn recording(&self) -> bool {
+        !self.checkpoints.is_empty()
+    }
+    /// Pushes a checkpoint.
+    fn push(&mut self) {
+        self.checkpoints.push(self.undo.len())
+    }
+    /// Pops a checkpoint, returns the undo entries to apply, most recent last.
+    ///
+    /// Returns `None` if there is no active checkpoint.
+    fn pop(&mut self) -> Option<::std::vec::Drain<'_, U>> {
+        let len = self.checkpoints.pop()?;
+        Some(self.undo.drain(len..))
+    }
+    /// Forgets the most recent checkpoint, its changes are now part of the previous one.
+    ///
+    /// Returns `false` if there is no active checkpoint.
+    fn forget(&mut self) -> bool {
+        if self.checkpoints.pop().is_none() {
+            false
+        } else {
+            if self.checkpoints.is_empty() {
+                self.undo.clear()
+            }
+            true
+        }
+    }
+}
+
+/// Undo entry of a [`TrailVec`](struct.TrailVec.html).
+#[derive(Clone, Debug)]
+enum VecUndo<T> {
+    /// An element was pushed.
+    Push,
+    /// An element was popped.
+    Pop(T),
+    /// An element was overwritten.
+    Set(usize, T),
+}
+
+/// Backtrackable [`IndexVec`](struct.IndexVec.html).
+///
+/// Mutations record undo entries when there is at least one active checkpoint.
+/// [`pop_checkpoint`](#method.pop_checkpoint) restores the state the vector was in when the
+/// matching [`push_checkpoint`](#method.push_checkpoint) was called, in time proportional to the
+/// number of mutations since then. Checkpoints can be nested.
+///
+/// Read-only access is available through `Deref`. Overwriting an element goes through
+/// [`set`](#method.set) or [`update`](#method.update), never through a mutable reference.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::TrailVec ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Vector indexed by variable indices."]
+///     map: VarMap with iter: VarMapIter
+/// }
+/// fn main() {
+///     let mut values: TrailVec<VarIndex, Option<bool>> = VarMap::of_elems(None, 3).into() ;
+///     let (v_0, v_1) = ( VarIndex::new(0), VarIndex::new(1) ) ;
+///     values.push_checkpoint() ;
+///     values.set( v_0, Some(true) ) ;
+///     values.push_checkpoint() ;
+///     values.set( v_1, Some(false) ) ;
+///     values.push(None) ;
+///     assert_eq!( values.len(), 4 ) ;
+///     assert!( values.pop_checkpoint() ) ;
+///     assert_eq!( values.as_slice().raw(), [ Some(true), None, None ] ) ;
+///     assert!( values.pop_checkpoint() ) ;
+///     assert_eq!( values.as_slice().raw(), [ None, None, None ] ) ;
+///     assert!( ! values.pop_checkpoint() ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrailVec<I, T> {
+    /// Elements.
+    vec: IndexVec<I, T>,
+    /// Trail.
+    trail: Trail<VecUndo<T>>,
+}
+impl<I, T> Default for TrailVec<I, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<I, T> From<IndexVec<I, T>> for TrailVec<I, T> {
+    fn from(vec: IndexVec<I, T>) -> Self {
+        TrailVec {
+            vec,
+            trail: Trail::new(),
+        }
+    }
+}
+impl<I, T> TrailVec<I, T> {
+    /// Empty vector.
+    #[inline]
+    pub fn new() -> Self {
+        IndexVec::new().into()
+    }
+    /// Empty vector with some capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        IndexVec::with_capacity(capacity).into()
+    }
+    /// Turns the vector into its current state, forgets all checkpoints.
+    #[inline]
+    pub fn into_index_vec(self) -> IndexVec<I, T> {
+        self.vec
+    }
+    /// Number of active checkpoints.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.trail.depth()
+    }
+    /// Pushes a checkpoint.
+    #[inline]
+    pub fn push_checkpoint(&mut self) {
+        self.trail.push()
+    }
+    /// Forgets the most recent checkpoint, without undoing its changes.
+    ///
+    /// Its changes will be undone when popping the previous checkpoint, if any. Returns `false`
+    /// if there is no active checkpoint.
+    #[inline]
+    pub fn forget_checkpoint(&mut self) -> bool {
+        self.trail.forget()
+    }
+    /// Undoes all changes since the most recent checkpoint, and removes it.
+    ///
+    /// Returns `false` if there is no active checkpoint.
+    pub fn pop_checkpoint(&mut self) -> bool {
+        let vec = &mut self.vec;
+        match self.trail.pop() {
+            None => false,
+            Some(undo) => {
+                for undo in undo.rev() {
+                    match undo {
+                        VecUndo::Push => {
+                            vec.pop();
+                        }
+                        VecUndo::Pop(elem) => vec.push(elem),
+                        VecUndo::Set(idx, elem) => vec.as_mut_slice().raw_mut()[idx] = elem,
+                    }
+                }
+                true
+            }
+        }
+    }
+    /// Pushes an element.
+    #[inline]
+    pub fn push(&mut self, elem: T) {
+        self.vec.push(elem);
+        self.trail.record(VecUndo::Push)
+    }
+    /// Pops an element.
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let elem = self.vec.pop()?;
+        if self.trail.recording() {
+            self.trail.record(VecUndo::Pop(elem.clone()))
+        }
+        Some(elem)
+    }
+}
+impl<I: IntWrap + From<usize>, T> TrailVec<I, T> {
+    /// Pushes an element, returns its index.
+    #[inline]
+    pub fn push_idx(&mut self, elem: T) -> I {
+        let idx = self.vec.next_index();
+        self.push(elem);
+        idx
+    }
+    /// Overwrites an element, returns the previous one.
+    pub fn set(&mut self, idx: I, elem: T) -> T
+    where
+        T: Clone,
+    {
+        let idx = idx.inner();
+        let old = ::std::mem::replace(&mut self.vec.as_mut_slice().raw_mut()[idx], elem);
+        if self.trail.recording() {
+            self.trail.record(VecUndo::Set(idx, old.clone()))
+        }
+        old
+    }
+    /// Applies a function to an element.
+    ///
+    /// Clones the element first if there is an active checkpoint.
+    pub fn update<Out, F: FnOnce(&mut T) -> Out>(&mut self, idx: I, f: F) -> Out
+    where
+        T: Clone,
+    {
+        let idx = idx.inner();
+        let elem = &mut self.vec.as_mut_slice().raw_mut()[idx];
+        if self.trail.recording() {
+            self.trail.record(VecUndo::Set(idx, elem.clone()))
+        }
+        f(elem)
+    }
+}
+impl<I, T> Deref for TrailVec<I, T> {
+    type Target = IndexVec<I, T>;
+    fn deref(&self) -> &IndexVec<I, T> {
+        &self.vec
+    }
+}
+
+/// Undo entry of a [`TrailHSet`](struct.TrailHSet.html).
+#[derive(Clone, Debug)]
+enum SetUndo<Int> {
+    /// An element was inserted.
+    Inserted(Int),
+    /// An element was removed.
+    Removed(Int),
+}
+
+/// Backtrackable [`IntHSet`](struct.IntHSet.html).
+///
+/// Same checkpoint mechanism as [`TrailVec`](struct.TrailVec.html). Read-only access is available
+/// through `Deref`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::TrailHSet ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+/// }
+/// fn main() {
+///     let mut set: TrailHSet<VarIndex> = TrailHSet::new() ;
+///     set.insert( VarIndex::new(0) ) ;
+///     set.push_checkpoint() ;
+///     set.insert( VarIndex::new(1) ) ;
+///     set.remove( & VarIndex::new(0) ) ;
+///     assert_eq!( set.len(), 1 ) ;
+///     set.pop_checkpoint() ;
+///     assert!( set.contains( & VarIndex::new(0) ) ) ;
+///     assert!( ! set.contains( & VarIndex::new(1) ) ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrailHSet<Int: IntHash, S = BuildHashUsize> {
+    /// Elements.
+    set: IntHSet<Int, S>,
+    /// Trail.
+    trail: Trail<SetUndo<Int>>,
+}
+impl<Int: IntHash, S: BuildHasher + Default> Default for TrailHSet<Int, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<Int: IntHash, S> From<IntHSet<Int, S>> for TrailHSet<Int, S> {
+    fn from(set: IntHSet<Int, S>) -> Self {
+        TrailHSet {
+            set,
+            trail: Trail::new(),
+        }
+    }
+}
+impl<Int: IntHash, S: BuildHasher + Default> TrailHSet<Int, S> {
+    /// Empty set.
+    #[inline]
+    pub fn new() -> Self {
+        IntHSet::new().into()
+    }
+    /// Empty set with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        IntHSet::with_capacity(capa).into()
+    }
+}
+impl<Int: IntHash + Clone, S: BuildHasher> TrailHSet<Int, S> {
+    /// Turns the set into its current state, forgets all checkpoints.
+    #[inline]
+    pub fn into_set(self) -> IntHSet<Int, S> {
+        self.set
+    }
+    /// Number of active checkpoints.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.trail.depth()
+    }
+    /// Pushes a checkpoint.
+    #[inline]
+    pub fn push_checkpoint(&mut self) {
+        self.trail.push()
+    }
+    /// Forgets the most recent checkpoint, without undoing its changes.
+    ///
+    /// Returns `false` if there is no active checkpoint.
+    #[inline]
+    pub fn forget_checkpoint(&mut self) -> bool {
+        self.trail.forget()
+    }
+    /// Undoes all changes since the most recent checkpoint, and removes it.
+    ///
+    /// Returns `false` if there is no active checkpoint.
+    pub fn pop_checkpoint(&mut self) -> bool {
+        let set = &mut self.set;
+        match self.trail.pop() {
+            None => false,
+            Some(undo) => {
+                for undo in undo.rev() {
+                    match undo {
+                        SetUndo::Inserted(elem) => {
+                            set.remove(&elem);
+                        }
+                        SetUndo::Removed(elem) => {
+                            set.insert(elem);
+                        }
+                    }
+                }
+                true
+            }
+        }
+    }
+    /// Inserts an element, returns `true` if it was not there.
+    pub fn insert(&mut self, elem: Int) -> bool {
+        if self.trail.recording() {
+            let is_new = self.set.insert(elem.clone());
+            if is_new {
+                self.trail.record(SetUndo::Inserted(elem))
+            }
+            is_new
+        } else {
+            self.set.insert(elem)
+        }
+    }
+    /// Removes an element, returns `true` if it was there.
+    pub fn remove(&mut self, elem: &Int) -> bool {
+        match self.set.take(elem) {
+            Some(elem) => {
+                self.trail.record(SetUndo::Removed(elem));
+                true
+            }
+            None => false,
+        }
+    }
+    /// Removes all the elements.
+    pub fn clear(&mut self) {
+        if self.trail.recording() {
+            for elem in self.set.drain() {
+                self.trail.record(SetUndo::Removed(elem))
+            }
+        } else {
+            self.set.clear()
+        }
+    }
+}
+impl<Int: IntHash, S> Deref for TrailHSet<Int, S> {
+    type Target = IntHSet<Int, S>;
+    fn deref(&self) -> &IntHSet<Int, S> {
+        &self.set
+    }
+}
+
+/// Undo entry of a [`TrailHMap`](struct.TrailHMap.html).
+#[derive(Clone, Debug)]
+enum MapUndo<Int, V> {
+    /// A key was inserted.
+    Inserted(Int),
+    /// A key was removed, or its value was overwritten.
+    Removed(Int, V),
+}
+
+/// Backtrackable [`IntHMap`](struct.IntHMap.html).
+///
+/// Same checkpoint mechanism as [`TrailVec`](struct.TrailVec.html). Read-only access is available
+/// through `Deref`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// use mylib::safe::int::TrailHMap ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+/// }
+/// fn main() {
+///     let mut map: TrailHMap<VarIndex, & str> = TrailHMap::new() ;
+///     let (v_0, v_1) = ( VarIndex::new(0), VarIndex::new(1) ) ;
+///     map.insert(v_0, "a") ;
+///     map.push_checkpoint() ;
+///     map.insert(v_0, "b") ;
+///     map.insert(v_1, "c") ;
+///     map.update( v_1, |val| * val = "d" ) ;
+///     assert_eq!( map.get(& v_1), Some(& "d") ) ;
+///     map.pop_checkpoint() ;
+///     assert_eq!( map.get(& v_0), Some(& "a") ) ;
+///     assert_eq!( map.get(& v_1), None ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrailHMap<Int: IntHash, V, S = BuildHashUsize> {
+    /// Bindings.
+    map: IntHMap<Int, V, S>,
+    /// Trail.
+    trail: Trail<MapUndo<Int, V>>,
+}
+impl<Int: IntHash, V, S: BuildHasher + Default> Default for TrailHMap<Int, V, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<Int: IntHash, V, S> From<IntHMap<Int, V, S>> for TrailHMap<Int, V, S> {
+    fn from(map: IntHMap<Int, V, S>) -> Self {
+        TrailHMap {
+            map,
+            trail: Trail::new(),
+        }
+    }
+}
+impl<Int: IntHash, V, S: BuildHasher + Default> TrailHMap<Int, V, S> {
+    /// Empty map.
+    #[inline]
+    pub fn new() -> Self {
+        IntHMap::new().into()
+    }
+    /// Empty map with some capacity.
+    #[inline]
+    pub fn with_capacity(capa: usize) -> Self {
+        IntHMap::with_capacity(capa).into()
+    }
+}
+impl<Int: IntHash + Clone, V, S: BuildHasher> TrailHMap<Int, V, S> {
+    /// Turns the map into its current state, forgets all checkpoints.
+    #[inline]
+    pub fn into_map(self) -> IntHMap<Int, V, S> {
+        self.map
+    }
+    /// Number of active checkpoints.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.trail.depth()
+    }
+    /// Pushes a checkpoint.
+    #[inline]
+    pub fn push_checkpoint(&mut self) {
+        self.trail.push()
+    }
+    /// Forgets the most recent checkpoint, without undoing its changes.
+    ///
+    /// Returns `false` if there is no active checkpoint.
+    #[inline]
+    pub fn forget_checkpoint(&mut self) -> bool {
+        self.trail.forget()
+    }
+    /// Undoes all changes since the most recent checkpoint, and removes it.
+    ///
+    /// Returns `false` if there is no active checkpoint.
+    pub fn pop_checkpoint(&mut self) -> bool {
+        let map = &mut self.map;
+        match self.trail.pop() {
+            None => false,
+            Some(undo) => {
+                for undo in undo.rev() {
+                    match undo {
+                        MapUndo::Inserted(key) => {
+                            map.remove(&key);
+                        }
+                        MapUndo::Removed(key, val) => {
+                            map.insert(key, val);
+                        }
+                    }
+                }
+                true
+            }
+        }
+    }
+    /// Removes a binding, returns the value if any.
+    pub fn remove(&mut self, key: &Int) -> Option<V>
+    where
+        V: Clone,
+    {
+        let (key, val) = self.map.remove_entry(key)?;
+        if self.trail.recording() {
+            self.trail.record(MapUndo::Removed(key, val.clone()))
+        }
+        Some(val)
+    }
+    /// Inserts a binding, returns the previous value if any.
+    pub fn insert(&mut self, key: Int, val: V) -> Option<V>
+    where
+        V: Clone,
+    {
+        if !self.trail.recording() {
+            return self.map.insert(key, val);
+        }
+        let old = self.map.insert(key.clone(), val);
+        let undo = match old {
+            Some(ref old) => MapUndo::Removed(key, old.clone()),
+            None => MapUndo::Inserted(key),
+        };
+        self.trail.record(undo);
+        old
+    }
+    /// Applies a function to the value of a key, `None` if the key is not bound.
+    ///
+    /// Clones the value first if there is an active checkpoint.
+    pub fn update<Out, F: FnOnce(&mut V) -> Out>(&mut self, key: Int, f: F) -> Option<Out>
+    where
+        V: Clone,
+    {
+        let val = self.map.get_mut(&key)?;
+        if self.trail.recording() {
+            self.trail.record(MapUndo::Removed(key, val.clone()))
+        }
+        Some(f(val))
+    }
+    /// Removes all the bindings.
+    pub fn clear(&mut self) {
+        if self.trail.recording() {
+            for (key, val) in self.map.drain() {
+                self.trail.record(MapUndo::Removed(key, val))
+            }
+        } else {
+            self.map.clear()
+        }
+    }
+}
+impl<Int: IntHash, V, S> Deref for TrailHMap<Int, V, S> {
+    type Target = IntHMap<Int, V, S>;
+    fn deref(&self) -> &IntHMap<Int, V, S> {
+        &self.map
+    }
+}
@@ -0,0 +1,248 @@
+//! Union-find over wrapped indices.
+
+use super::{IndexVec, IntRange, IntWrap};
+
+/// Information about an element of a union-find.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Node<I> {
+    /// Parent of the element, itself for representatives.
+    parent: I,
+    /// Next element in the class of the element, the classes are circular lists.
+    next: I,
+    /// Upper bound on the height of the tree below the element, only relevant for
+    /// representatives.
+    rank: u32,
+    /// Size of the class, only relevant for representatives.
+    size: usize,
+}
+
+/// Union-find (disjoint sets) over wrapped indices.
+///
+/// This is what the `union find` tag of [`wrap_usize`](../../macro.wrap_usize.html) expands to, as
+/// a type alias. Elements are the indices from `0` to `len()` excluded, each element starts in its
+/// own class. Uses path compression and union by rank, backed by an
+/// [`IndexVec`](struct.IndexVec.html).
+///
+/// Since [`find`](#method.find) compresses paths, it takes `&mut self`.
+/// [`find_no_compress`](#method.find_no_compress) does not.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate mylib ;
+/// wrap_usize!{
+///     #[doc = "Index of a variable."]
+///     VarIndex
+///     #[doc = "Equivalence classes of variables."]
+///     union find: VarUf
+/// }
+/// fn main() {
+///     let mut uf = VarUf::with_len(5) ;
+///     let (v_0, v_1, v_2, v_3) = (
+///         VarIndex::new(0), VarIndex::new(1), VarIndex::new(2), VarIndex::new(3)
+///     ) ;
+///     let rep = uf.union(v_0, v_1) ;
+///     assert!( rep == v_0 || rep == v_1 ) ;
+///     uf.union(v_3, v_1) ;
+///     assert!( uf.same(v_0, v_3) ) ;
+///     assert!( ! uf.same(v_0, v_2) ) ;
+///     assert_eq!( uf.class_size(v_3), 3 ) ;
+///     assert_eq!( uf.class_count(), 3 ) ;
+///
+///     let mut class: Vec<_> = uf.class(v_1).collect() ;
+///     class.sort() ;
+///     assert_eq!( class, vec![v_0, v_1, v_3] ) ;
+///
+///     let v_5 = uf.push() ;
+///     assert_eq!( v_5, VarIndex::new(5) ) ;
+///     assert_eq!( uf.class_size(v_5), 1 ) ;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnionFind<I> {
+    /// Elements.
+    nodes: IndexVec<I, Node<I>>,
+    /// Number of classes.
+    class_count: usize,
+}
+
+impl<I> Default for UnionFind<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> UnionFind<I> {
+    /// Empty union-find.
+    #[inline]
+    pub fn new() -> Self {
+        UnionFind {
+            nodes: IndexVec::new(),
+            class_count: 0,
+        }
+    }
+    /// Empty union-find with some capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        UnionFind {
+            nodes: IndexVec::with_capacity(capacity),
+            class_count: 0,
+        }
+    }
+    /// Number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    /// True if there are no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// Number of classes.
+    #[inline]
+    pub fn class_count(&self) -> usize {
+        self.class_count
+    }
+}
+
+impl<I: IntWrap + From<usize> + Copy> UnionFind<I> {
+    /// Union-find with `len` elements, each in its own class.
+    pub fn with_len(len: usize) -> Self {
+        let mut uf = Self::with_capacity(len);
+        uf.grow_to(len);
+        uf
+    }
+    /// Adds an element in its own class, returns it.
+    pub fn push(&mut self) -> I {
+        let elem = self.nodes.next_index();
+        self.nodes.push(Node {
+            parent: elem,
+            next: elem,
+            rank: 0,
+            size: 1,
+        });
+        self.class_count += 1;
+        elem
+    }
+    /// Adds elements, each in its own class, until there are `len` elements.
+    pub fn grow_to(&mut self, len: usize) {
+        while self.nodes.len() < len {
+            self.push();
+        }
+    }
+    /// The elements.
+    #[inline]
+    pub fn indices(&self) -> IntRange<I> {
+        self.nodes.indices()
+    }
+
+    /// Representative of the class of an element, compresses the path to it.
+    pub fn find(&mut self, elem: I) -> I {
+        let rep = self.find_no_compress(elem);
+        let mut current = elem;
+        while current.inner() != rep.inner() {
+            let parent = self.nodes[current].parent;
+            self.nodes[current].parent = rep;
+            current = parent
+        }
+        rep
+    }
+    /// Representative of the class of an element, without path compression.
+    pub fn find_no_compress(&self, elem: I) -> I {
+        let mut current = elem;
+        loop {
+            let parent = self.nodes[current].parent;
+            if parent.inner() == current.inner() {
+                return current;
+            }
+            current = parent
+        }
+    }
+    /// True if an element is the representative of its class.
+    #[inline]
+    pub fn is_rep(&self, elem: I) -> bool {
+        self.nodes[elem].parent.inner() == elem.inner()
+    }
+    /// True if two elements are in the same class.
+    #[inline]
+    pub fn same(&mut self, lft: I, rgt: I) -> bool {
+        self.find(lft).inner() == self.find(rgt).inner()
+    }
+
+    /// Merges the classes of two elements, returns the representative of the new class.
+    pub fn union(&mut self, lft: I, rgt: I) -> I {
+        let (lft, rgt) = (self.find(lft), self.find(rgt));
+        if lft.inner() == rgt.inner() {
+            return lft;
+        }
+        let (rep, sub) = if self.nodes[lft].rank < self.nodes[rgt].rank {
+            (rgt, lft)
+        } else {
+            (lft, rgt)
+        };
+        if self.nodes[rep].rank == self.nodes[sub].rank {
+            self.nodes[rep].rank += 1
+        }
+        self.nodes[sub].parent = rep;
+        self.nodes[rep].size += self.nodes[sub].size;
+        // Splice the two circular lists.
+        let rep_next = self.nodes[rep].next;
+        self.nodes[rep].next = self.nodes[sub].next;
+        self.nodes[sub].next = rep_next;
+        self.class_count -= 1;
+        rep
+    }
+
+    /// Number of elements in the class of an element.
+    #[inline]
+    pub fn class_size(&mut self, elem: I) -> usize {
+        let rep = self.find(elem);
+        self.nodes[rep].size
+    }
+    /// Iterates over the elements of the class of an element, starting with that element.
+    ///
+    /// Runs in time linear in the size of the class.
+    #[inline]
+    pub fn class(&self, elem: I) -> UnionFindClass<'_, I> {
+        UnionFindClass {
+            nodes: &self.nodes,
+            start: elem,
+            next: Some(elem),
+        }
+    }
+    /// Iterates over the representatives of the classes.
+    pub fn reps(&self) -> impl Iterator<Item = I> + '_ {
+        self.nodes
+            .index_iter()
+            .filter(|(elem, node)| elem.inner() == node.parent.inner())
+            .map(|(elem, _)| elem)
+    }
+    /// Iterates over the classes.
+    pub fn classes(&self) -> impl Iterator<Item = UnionFindClass<'_, I>> + '_ {
+        self.reps().map(move |rep| self.class(rep))
+    }
+}
+
+/// Iterator over the elements of a class of a [`UnionFind`](struct.UnionFind.html).
+#[derive(Clone)]
+pub struct UnionFindClass<'a, I> {
+    /// Elements of the union-find.
+    nodes: &'a IndexVec<I, Node<I>>,
+    /// Element the iteration started from.
+    start: I,
+    /// Next element, if any.
+    next: Option<I>,
+}
+impl<'a, I: IntWrap + Copy> Iterator for UnionFindClass<'a, I> {
+    type Item = I;
+    fn next(&mut self) -> Option<I> {
+        let current = self.next?;
+        let next = self.nodes[current].next;
+        self.next = if next.inner() == self.start.inner() {
+            None
+        } else {
+            Some(next)
+        };
+        Some(current)
+    }
+}
@@ -1,3 +1,5 @@
 //! Zero-cost strong typing stuff.
 
+pub mod graph ;
+pub mod hcons ;
 pub mod int ;
\ No newline at end of file